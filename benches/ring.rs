@@ -0,0 +1,124 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+//! Benchmarks comparing the modulo based `RingBuf` against the bit
+//! masking based `Pow2RingBuf`.
+
+use criterion::black_box;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::Criterion;
+
+use rbuf::Pow2RingBuf;
+use rbuf::RingBuf;
+
+
+/// The length of the ring buffers we benchmark with.
+const LEN: usize = 1024;
+
+
+/// Benchmark pushing elements to the back of a ring buffer.
+fn push_back(c: &mut Criterion) {
+  let mut group = c.benchmark_group("push_back");
+
+  let mut buf = RingBuf::<u64>::new(LEN);
+  let _ = group.bench_function("RingBuf", |b| {
+    b.iter(|| {
+      for i in 0..LEN as u64 {
+        let () = buf.push_back(black_box(i));
+      }
+    })
+  });
+
+  let mut buf = Pow2RingBuf::<u64>::new(LEN);
+  let _ = group.bench_function("Pow2RingBuf", |b| {
+    b.iter(|| {
+      for i in 0..LEN as u64 {
+        let () = buf.push_back(black_box(i));
+      }
+    })
+  });
+}
+
+/// Benchmark pushing elements to the front of a ring buffer while
+/// accessing its front and back elements.
+fn front_back(c: &mut Criterion) {
+  let mut group = c.benchmark_group("front_back");
+
+  let mut buf = RingBuf::<u64>::new(LEN);
+  let _ = group.bench_function("RingBuf", |b| {
+    b.iter(|| {
+      (0..LEN as u64).fold(0u64, |acc, i| {
+        let () = buf.push_front(black_box(i));
+        acc.wrapping_add(*buf.front()).wrapping_add(*buf.back())
+      })
+    })
+  });
+
+  let mut buf = Pow2RingBuf::<u64>::new(LEN);
+  let _ = group.bench_function("Pow2RingBuf", |b| {
+    b.iter(|| {
+      (0..LEN as u64).fold(0u64, |acc, i| {
+        let () = buf.push_front(black_box(i));
+        acc.wrapping_add(*buf.front()).wrapping_add(*buf.back())
+      })
+    })
+  });
+}
+
+/// Benchmark random access into a ring buffer.
+fn index(c: &mut Criterion) {
+  let mut group = c.benchmark_group("index");
+
+  let mut buf = RingBuf::<u64>::new(LEN);
+  let () = buf.push_back(42);
+  let _ = group.bench_function("RingBuf", |b| {
+    b.iter(|| {
+      (0..LEN)
+        .map(|idx| buf[black_box(idx * 7)])
+        .fold(0u64, u64::wrapping_add)
+    })
+  });
+
+  let mut buf = Pow2RingBuf::<u64>::new(LEN);
+  let () = buf.push_back(42);
+  let _ = group.bench_function("Pow2RingBuf", |b| {
+    b.iter(|| {
+      (0..LEN)
+        .map(|idx| buf[black_box(idx * 7)])
+        .fold(0u64, u64::wrapping_add)
+    })
+  });
+}
+
+/// Benchmark iteration over a ring buffer.
+fn iter(c: &mut Criterion) {
+  let mut group = c.benchmark_group("iter");
+
+  let mut buf = RingBuf::<u64>::new(LEN);
+  let () = buf.push_back(42);
+  let _ = group.bench_function("RingBuf", |b| {
+    b.iter(|| black_box(&buf).iter().fold(0u64, |acc, x| acc.wrapping_add(*x)))
+  });
+
+  let mut buf = Pow2RingBuf::<u64>::new(LEN);
+  let () = buf.push_back(42);
+  let _ = group.bench_function("Pow2RingBuf", |b| {
+    b.iter(|| black_box(&buf).iter().fold(0u64, |acc, x| acc.wrapping_add(*x)))
+  });
+}
+
+
+// The functions generated by `criterion_group!` lack documentation.
+#[allow(missing_docs)]
+mod group {
+  use super::criterion_group;
+  use super::front_back;
+  use super::index;
+  use super::iter;
+  use super::push_back;
+
+  criterion_group!(benches, push_back, front_back, index, iter);
+}
+
+criterion_main!(group::benches);
@@ -0,0 +1,47 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use allocator_api2::alloc::Allocator;
+use allocator_api2::boxed::Box;
+use allocator_api2::vec::Vec;
+
+use crate::RingBuf;
+use crate::Storage;
+
+
+/// Constructors for ring buffers with storage allocated through a
+/// custom allocator.
+impl<T, A> RingBuf<T, Box<[T], A>>
+where
+  A: Allocator,
+{
+  /// Create a new `RingBuf` of a fixed length as provided, allocating
+  /// storage through `alloc`.
+  ///
+  /// # Panics
+  /// This constructor panics if `len` is zero.
+  pub fn new_in(len: usize, alloc: A) -> Self
+  where
+    T: Default,
+  {
+    let mut vec = Vec::with_capacity_in(len, alloc);
+    vec.resize_with(len, Default::default);
+
+    Self::from_vec_in(vec)
+  }
+
+  /// Create a new `RingBuf` with data from a `Vec` allocated through a
+  /// custom allocator.
+  ///
+  /// Note that the vector's first element is considered the front.
+  ///
+  /// # Panics
+  /// This constructor panics if the provided vector is empty.
+  #[inline]
+  pub fn from_vec_in(vec: Vec<T, A>) -> Self {
+    Self::from_storage(vec.into_boxed_slice())
+  }
+}
+
+// SAFETY: A boxed slice cannot change its length.
+unsafe impl<T, A> Storage<T> for Box<[T], A> where A: Allocator {}
@@ -0,0 +1,248 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+//! An asynchronous bounded channel with overwrite-oldest semantics.
+//!
+//! A channel is created by means of [`channel`], which returns a
+//! [`Sender`] and a [`Receiver`]. Sending never blocks: once the
+//! channel is full, each sent element evicts the oldest buffered one.
+//! The receiver implements [`Stream`], the sender implements [`Sink`].
+//!
+//! The stream ends once all senders have been dropped and all
+//! buffered elements have been received.
+//!
+//! # Examples
+//! ```rust
+//! # use futures::executor::block_on;
+//! # use futures::StreamExt as _;
+//! use rbuf::async_channel::channel;
+//!
+//! let (sender, receiver) = channel(2);
+//! for i in 0..4 {
+//!   let _evicted = sender.send(i).unwrap();
+//! }
+//! drop(sender);
+//!
+//! let received = block_on(receiver.collect::<Vec<_>>());
+//! assert_eq!(received, vec![2, 3]);
+//! ```
+
+use core::pin::Pin;
+use core::task::Context;
+use core::task::Poll;
+use core::task::Waker;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+
+use futures_core::Stream;
+use futures_sink::Sink;
+
+use crate::BoundedRing;
+use crate::Closed;
+
+
+/// The state shared between the senders and the receiver.
+#[derive(Debug)]
+struct State<T> {
+  /// The buffered elements.
+  ring: BoundedRing<T>,
+  /// The waker of the receiver's task, if it is waiting for elements.
+  waker: Option<Waker>,
+  /// The number of senders alive.
+  senders: usize,
+  /// Whether the receiver is still alive.
+  receiver: bool,
+}
+
+/// The lock protected state shared between the senders and the
+/// receiver.
+#[derive(Debug)]
+struct Shared<T>(Mutex<State<T>>);
+
+impl<T> Shared<T> {
+  /// Lock the shared state.
+  ///
+  /// None of our operations can leave the state inconsistent, so we
+  /// ignore lock poisoning.
+  #[inline]
+  fn lock(&self) -> MutexGuard<'_, State<T>> {
+    self.0.lock().unwrap_or_else(PoisonError::into_inner)
+  }
+
+  /// Wake the receiver's task, if it is waiting.
+  #[inline]
+  fn wake(mut state: MutexGuard<'_, State<T>>) {
+    let waker = state.waker.take();
+    drop(state);
+
+    if let Some(waker) = waker {
+      let () = waker.wake();
+    }
+  }
+}
+
+
+/// Create a new asynchronous channel buffering up to `capacity`
+/// elements.
+///
+/// # Panics
+/// This function panics if `capacity` is zero.
+pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+  let shared = Arc::new(Shared(Mutex::new(State {
+    ring: BoundedRing::new(capacity),
+    waker: None,
+    senders: 1,
+    receiver: true,
+  })));
+
+  let sender = Sender {
+    shared: Arc::clone(&shared),
+  };
+  let receiver = Receiver { shared };
+  (sender, receiver)
+}
+
+
+/// The sending half of an asynchronous channel.
+///
+/// Senders can be cloned to send from multiple tasks.
+#[derive(Debug)]
+pub struct Sender<T> {
+  /// The state shared with the receiver and other senders.
+  shared: Arc<Shared<T>>,
+}
+
+impl<T> Sender<T> {
+  /// Send an element through the channel, evicting the oldest buffered
+  /// element if the channel is full.
+  ///
+  /// The evicted element, if any, is returned. If the receiver has been
+  /// dropped, the element is handed back as an error.
+  pub fn send(&self, elem: T) -> Result<Option<T>, Closed<T>> {
+    let mut state = self.shared.lock();
+    if !state.receiver {
+      return Err(Closed(elem))
+    }
+
+    let evicted = state.ring.push_back(elem);
+    let () = Shared::wake(state);
+    Ok(evicted)
+  }
+
+  /// Check whether the receiver has been dropped.
+  #[inline]
+  pub fn is_closed(&self) -> bool {
+    !self.shared.lock().receiver
+  }
+}
+
+impl<T> Clone for Sender<T> {
+  fn clone(&self) -> Self {
+    self.shared.lock().senders += 1;
+    Self {
+      shared: Arc::clone(&self.shared),
+    }
+  }
+}
+
+impl<T> Drop for Sender<T> {
+  fn drop(&mut self) {
+    let mut state = self.shared.lock();
+    state.senders -= 1;
+    if state.senders == 0 {
+      // Wake the receiver so that it can observe the end of the stream.
+      let () = Shared::wake(state);
+    }
+  }
+}
+
+/// A `Sender` is always ready to accept an element, because sending
+/// evicts the oldest element instead of waiting for space.
+impl<T> Sink<T> for Sender<T> {
+  type Error = Closed<T>;
+
+  fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    Poll::Ready(Ok(()))
+  }
+
+  fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+    self.send(item).map(|_evicted| ())
+  }
+
+  fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    Poll::Ready(Ok(()))
+  }
+
+  fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    Poll::Ready(Ok(()))
+  }
+}
+
+
+/// The receiving half of an asynchronous channel.
+#[derive(Debug)]
+pub struct Receiver<T> {
+  /// The state shared with the senders.
+  shared: Arc<Shared<T>>,
+}
+
+impl<T> Receiver<T> {
+  /// Receive the oldest buffered element, if any, without waiting.
+  #[inline]
+  pub fn try_recv(&mut self) -> Option<T> {
+    self.shared.lock().ring.pop_front()
+  }
+
+  /// Retrieve the number of buffered elements.
+  #[inline]
+  pub fn len(&self) -> usize {
+    self.shared.lock().ring.len()
+  }
+
+  /// Check whether no elements are buffered.
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.shared.lock().ring.is_empty()
+  }
+}
+
+impl<T> Drop for Receiver<T> {
+  fn drop(&mut self) {
+    self.shared.lock().receiver = false;
+  }
+}
+
+impl<T> Stream for Receiver<T> {
+  type Item = T;
+
+  fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    let mut state = self.shared.lock();
+    if let Some(elem) = state.ring.pop_front() {
+      return Poll::Ready(Some(elem))
+    }
+
+    if state.senders == 0 {
+      return Poll::Ready(None)
+    }
+
+    // Only replace the stored waker if it would not wake the same task
+    // anyway, to avoid needless clones.
+    match &state.waker {
+      Some(waker) if waker.will_wake(cx.waker()) => (),
+      _ => state.waker = Some(cx.waker().clone()),
+    }
+    Poll::Pending
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let state = self.shared.lock();
+    let len = state.ring.len();
+    if state.senders == 0 {
+      (len, Some(len))
+    } else {
+      (len, None)
+    }
+  }
+}
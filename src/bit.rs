@@ -0,0 +1,221 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use core::iter::FusedIterator;
+use core::ops::RangeBounds;
+
+use crate::bits::Bits;
+use crate::util::split_range;
+use crate::util::to_range;
+
+
+/// A ring buffer of bits.
+///
+/// A `BitRingBuf` is semantically equivalent to a `RingBuf<bool>`, but
+/// it packs its bits into `u64` words. As such, it requires only an
+/// eighth of the memory and it can count the number of set bits in a
+/// range a word at a time.
+///
+/// Just like a [`RingBuf`][crate::RingBuf], a `BitRingBuf` is always
+/// "full", with bits initially being cleared. Indexing works the same
+/// as well: index `0` refers to the front bit, `self.len() - 1` to the
+/// back one, and indexes wrap around at the ring buffer's end.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BitRingBuf {
+  /// Our actual data.
+  bits: Bits,
+  /// The number of bits in the ring buffer.
+  len: usize,
+  /// The storage index of the front bit.
+  front: usize,
+}
+
+#[allow(clippy::len_without_is_empty)]
+impl BitRingBuf {
+  /// Create a new `BitRingBuf` of `len` bits, all of which are cleared.
+  ///
+  /// # Panics
+  /// This constructor panics if `len` is zero.
+  pub fn new(len: usize) -> Self {
+    assert_ne!(len, 0);
+
+    Self {
+      bits: Bits::new(len),
+      len,
+      front: 0,
+    }
+  }
+
+  /// Retrieve the ring buffer's length, in bits.
+  #[inline]
+  pub const fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Map a logical index to an index into our storage.
+  #[inline]
+  fn physical_idx(&self, idx: usize) -> usize {
+    (self.front + idx) % self.len
+  }
+
+  /// Retrieve the storage index of the back bit.
+  #[inline]
+  fn back_idx(&self) -> usize {
+    self.front.checked_sub(1).unwrap_or(self.len - 1)
+  }
+
+  /// Retrieve the bit at the provided logical index.
+  #[inline]
+  pub fn get(&self, idx: usize) -> bool {
+    self.bits.get(self.physical_idx(idx))
+  }
+
+  /// Set the bit at the provided logical index, returning its previous
+  /// value.
+  #[inline]
+  pub fn set(&mut self, idx: usize, value: bool) -> bool {
+    self.bits.set(self.physical_idx(idx), value)
+  }
+
+  /// Retrieve the front bit.
+  #[inline]
+  pub fn front(&self) -> bool {
+    self.bits.get(self.front)
+  }
+
+  /// Retrieve the back bit.
+  #[inline]
+  pub fn back(&self) -> bool {
+    self.bits.get(self.back_idx())
+  }
+
+  /// Push a bit to the front of the ring buffer.
+  ///
+  /// This operation will push a new bit before the current front into
+  /// the ring buffer and make it the new front. The back bit gets
+  /// replaced in the process.
+  #[inline]
+  pub fn push_front(&mut self, value: bool) {
+    let idx = self.back_idx();
+    let _prev = self.bits.set(idx, value);
+    self.front = idx;
+  }
+
+  /// Push a bit to the back of the ring buffer.
+  ///
+  /// This operation will push a new bit after the current back into the
+  /// ring buffer and make it the new back. The front bit gets replaced
+  /// in the process.
+  #[inline]
+  pub fn push_back(&mut self, value: bool) {
+    let idx = self.front;
+    let _prev = self.bits.set(idx, value);
+    self.front = (idx + 1) % self.len;
+  }
+
+  /// Pop the front bit from the ring buffer.
+  ///
+  /// This operation will clear the front bit and return its previous
+  /// value. The bit after the current front will become the new front.
+  #[inline]
+  pub fn pop_front(&mut self) -> bool {
+    let idx = self.front;
+    self.front = (idx + 1) % self.len;
+    self.bits.set(idx, false)
+  }
+
+  /// Pop the back bit from the ring buffer.
+  ///
+  /// This operation will clear the back bit and return its previous
+  /// value. The bit before the current back will become the new back.
+  #[inline]
+  pub fn pop_back(&mut self) -> bool {
+    let idx = self.back_idx();
+    self.front = idx;
+    self.bits.set(idx, false)
+  }
+
+  /// Count the number of set bits in the ring buffer.
+  #[inline]
+  pub fn count_ones(&self) -> usize {
+    self.bits.count_ones(0..self.len)
+  }
+
+  /// Count the number of set bits in the provided range of logical
+  /// indexes.
+  ///
+  /// # Panics
+  /// This method panics if the range is decreasing or extends past the
+  /// ring buffer's length.
+  pub fn count_ones_range<R>(&self, range: R) -> usize
+  where
+    R: RangeBounds<usize>,
+  {
+    let range = to_range(range, self.len);
+    let (first, second) = split_range(self.len, self.front, range);
+    self.bits.count_ones(first) + self.bits.count_ones(second)
+  }
+
+  /// Retrieve an iterator over the bits of the ring buffer.
+  ///
+  /// The iterator traverses the ring buffer in front-to-back manner.
+  #[inline]
+  pub fn iter(&self) -> BitIter<'_> {
+    BitIter {
+      buf: self,
+      next: 0,
+      next_back: self.len,
+    }
+  }
+}
+
+
+/// An iterator over the bits of a [`BitRingBuf`].
+///
+/// Iteration happens front-to-back, unless reversed.
+#[derive(Clone, Debug)]
+pub struct BitIter<'b> {
+  /// The ring buffer we iterate over.
+  buf: &'b BitRingBuf,
+  /// The index of the next bit to yield in forward direction.
+  next: usize,
+  /// The index of the next bit to yield in backward direction.
+  next_back: usize,
+}
+
+impl Iterator for BitIter<'_> {
+  type Item = bool;
+
+  #[inline]
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.next < self.next_back {
+      let idx = self.next;
+      self.next += 1;
+      Some(self.buf.get(idx))
+    } else {
+      None
+    }
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let len = self.next_back - self.next;
+    (len, Some(len))
+  }
+}
+
+impl DoubleEndedIterator for BitIter<'_> {
+  #[inline]
+  fn next_back(&mut self) -> Option<Self::Item> {
+    if self.next < self.next_back {
+      self.next_back -= 1;
+      Some(self.buf.get(self.next_back))
+    } else {
+      None
+    }
+  }
+}
+
+impl ExactSizeIterator for BitIter<'_> {}
+
+impl FusedIterator for BitIter<'_> {}
@@ -0,0 +1,121 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use alloc::boxed::Box;
+use alloc::vec;
+use core::ops::Range;
+
+
+/// The number of bits stored in a single word.
+const WORD_BITS: usize = u64::BITS as usize;
+
+
+/// A fixed-size set of bits, packed into `u64` words.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct Bits {
+  /// The words storing our bits. Bits beyond `len` are always zero.
+  words: Box<[u64]>,
+  /// The number of bits stored.
+  len: usize,
+}
+
+impl Bits {
+  /// Create a new `Bits` object of `len` bits, all cleared.
+  pub fn new(len: usize) -> Self {
+    let words = vec![0; (len + WORD_BITS - 1) / WORD_BITS];
+    Self {
+      words: words.into_boxed_slice(),
+      len,
+    }
+  }
+
+  /// Retrieve the value of the bit at index `idx`.
+  #[inline]
+  pub fn get(&self, idx: usize) -> bool {
+    debug_assert!(idx < self.len, "idx: {idx}, len: {}", self.len);
+    self.words[idx / WORD_BITS] & (1 << (idx % WORD_BITS)) != 0
+  }
+
+  /// Set the bit at index `idx` to `value`, returning its previous
+  /// value.
+  #[inline]
+  pub fn set(&mut self, idx: usize, value: bool) -> bool {
+    debug_assert!(idx < self.len, "idx: {idx}, len: {}", self.len);
+    let word = &mut self.words[idx / WORD_BITS];
+    let mask = 1 << (idx % WORD_BITS);
+    let prev = *word & mask != 0;
+    if value {
+      *word |= mask;
+    } else {
+      *word &= !mask;
+    }
+    prev
+  }
+
+  /// Create a mask of the bits in the word at index `word` that fall
+  /// into `range`.
+  #[inline]
+  fn mask(word: usize, range: &Range<usize>) -> u64 {
+    let start = word * WORD_BITS;
+    let lo = range.start.saturating_sub(start);
+    let hi = (range.end - start).min(WORD_BITS);
+    debug_assert!(lo < hi && hi <= WORD_BITS);
+
+    (u64::MAX >> (WORD_BITS - (hi - lo))) << lo
+  }
+
+  /// Count the number of set bits in the provided range of indexes.
+  pub fn count_ones(&self, range: Range<usize>) -> usize {
+    debug_assert!(range.end <= self.len);
+
+    if range.start >= range.end {
+      return 0
+    }
+
+    let first = range.start / WORD_BITS;
+    let last = (range.end - 1) / WORD_BITS;
+    (first..=last)
+      .map(|word| (self.words[word] & Self::mask(word, &range)).count_ones() as usize)
+      .sum()
+  }
+
+  /// Find the index of the first set bit in the provided range.
+  pub fn first_one(&self, range: Range<usize>) -> Option<usize> {
+    debug_assert!(range.end <= self.len);
+
+    if range.start >= range.end {
+      return None
+    }
+
+    let first = range.start / WORD_BITS;
+    let last = (range.end - 1) / WORD_BITS;
+    (first..=last).find_map(|word| {
+      let bits = self.words[word] & Self::mask(word, &range);
+      if bits != 0 {
+        Some(word * WORD_BITS + bits.trailing_zeros() as usize)
+      } else {
+        None
+      }
+    })
+  }
+
+  /// Find the index of the last set bit in the provided range.
+  pub fn last_one(&self, range: Range<usize>) -> Option<usize> {
+    debug_assert!(range.end <= self.len);
+
+    if range.start >= range.end {
+      return None
+    }
+
+    let first = range.start / WORD_BITS;
+    let last = (range.end - 1) / WORD_BITS;
+    (first..=last).rev().find_map(|word| {
+      let bits = self.words[word] & Self::mask(word, &range);
+      if bits != 0 {
+        Some(word * WORD_BITS + (WORD_BITS - 1 - bits.leading_zeros() as usize))
+      } else {
+        None
+      }
+    })
+  }
+}
@@ -0,0 +1,263 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use core::fmt::Debug;
+use core::fmt::Display;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use core::time::Duration;
+use std::error::Error;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+
+use crate::BoundedRing;
+
+
+/// The error returned when pushing to a closed [`SyncRingBuf`].
+///
+/// The element that could not be pushed is handed back.
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct Closed<T>(pub T);
+
+impl<T> Debug for Closed<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.write_str("Closed(..)")
+  }
+}
+
+impl<T> Display for Closed<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.write_str("ring buffer is closed")
+  }
+}
+
+impl<T> Error for Closed<T> {}
+
+
+/// The state protected by the mutex of a [`SyncRingBuf`].
+#[derive(Debug)]
+struct State<T> {
+  /// The buffered elements.
+  ring: BoundedRing<T>,
+  /// Whether the ring buffer has been closed.
+  closed: bool,
+}
+
+
+/// A ring buffer for exchanging elements between threads, allowing
+/// them to block until elements or space become available.
+///
+/// A `SyncRingBuf` is usually shared between threads by wrapping it in
+/// an `Arc`. Once [closed][SyncRingBuf::close], no more elements can be
+/// pushed, while remaining elements can still be popped. All blocked
+/// threads are woken up.
+///
+/// # Examples
+/// ```rust
+/// # use std::sync::Arc;
+/// # use std::thread;
+/// # use rbuf::SyncRingBuf;
+/// let buf = Arc::new(SyncRingBuf::new(4));
+/// let producer = {
+///   let buf = Arc::clone(&buf);
+///   thread::spawn(move || {
+///     for i in 0..16 {
+///       let () = buf.push_blocking(i).unwrap();
+///     }
+///     let () = buf.close();
+///   })
+/// };
+///
+/// let mut next = 0;
+/// while let Some(i) = buf.pop_blocking() {
+///   assert_eq!(i, next);
+///   next += 1;
+/// }
+/// assert_eq!(next, 16);
+/// let () = producer.join().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct SyncRingBuf<T> {
+  /// The buffered elements, along with the closed state.
+  state: Mutex<State<T>>,
+  /// The condition variable signaled when an element got pushed or the
+  /// ring buffer got closed.
+  pushed: Condvar,
+  /// The condition variable signaled when an element got popped or the
+  /// ring buffer got closed.
+  popped: Condvar,
+}
+
+impl<T> SyncRingBuf<T> {
+  /// Create a new, empty `SyncRingBuf` with capacity for `capacity`
+  /// elements.
+  ///
+  /// # Panics
+  /// This constructor panics if `capacity` is zero.
+  pub fn new(capacity: usize) -> Self {
+    Self {
+      state: Mutex::new(State {
+        ring: BoundedRing::new(capacity),
+        closed: false,
+      }),
+      pushed: Condvar::new(),
+      popped: Condvar::new(),
+    }
+  }
+
+  /// Lock our state.
+  ///
+  /// None of our operations can leave the state inconsistent, so we
+  /// ignore lock poisoning.
+  #[inline]
+  fn lock(&self) -> MutexGuard<'_, State<T>> {
+    self.state.lock().unwrap_or_else(PoisonError::into_inner)
+  }
+
+  /// Retrieve the ring buffer's capacity.
+  #[inline]
+  pub fn capacity(&self) -> usize {
+    self.lock().ring.capacity()
+  }
+
+  /// Retrieve the number of elements in the ring buffer.
+  #[inline]
+  pub fn len(&self) -> usize {
+    self.lock().ring.len()
+  }
+
+  /// Check whether the ring buffer is empty.
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.lock().ring.is_empty()
+  }
+
+  /// Check whether the ring buffer has been closed.
+  #[inline]
+  pub fn is_closed(&self) -> bool {
+    self.lock().closed
+  }
+
+  /// Push an element into the ring buffer, evicting the oldest element
+  /// if it is full.
+  ///
+  /// The evicted element, if any, is returned. If the ring buffer has
+  /// been closed, the element is handed back as an error.
+  pub fn push(&self, elem: T) -> Result<Option<T>, Closed<T>> {
+    let mut state = self.lock();
+    if state.closed {
+      return Err(Closed(elem))
+    }
+
+    let evicted = state.ring.push_back(elem);
+    drop(state);
+    let () = self.pushed.notify_all();
+    Ok(evicted)
+  }
+
+  /// Push an element into the ring buffer, blocking until space is
+  /// available if it is full.
+  ///
+  /// If the ring buffer has been closed, the element is handed back as
+  /// an error.
+  pub fn push_blocking(&self, elem: T) -> Result<(), Closed<T>> {
+    let state = self.lock();
+    let mut state = self
+      .popped
+      .wait_while(state, |state| !state.closed && state.ring.is_full())
+      .unwrap_or_else(PoisonError::into_inner);
+    if state.closed {
+      return Err(Closed(elem))
+    }
+
+    let _evicted = state.ring.push_back(elem);
+    drop(state);
+    let () = self.pushed.notify_all();
+    Ok(())
+  }
+
+  /// Pop the oldest element from the ring buffer, if any.
+  pub fn try_pop(&self) -> Option<T> {
+    let elem = self.lock().ring.pop_front();
+    if elem.is_some() {
+      let () = self.popped.notify_one();
+    }
+    elem
+  }
+
+  /// Pop the oldest element from the ring buffer, blocking until one is
+  /// available if it is empty.
+  ///
+  /// `None` is returned once the ring buffer is both closed and empty.
+  pub fn pop_blocking(&self) -> Option<T> {
+    let state = self.lock();
+    let mut state = self
+      .pushed
+      .wait_while(state, |state| !state.closed && state.ring.is_empty())
+      .unwrap_or_else(PoisonError::into_inner);
+    let elem = state.ring.pop_front();
+    drop(state);
+
+    if elem.is_some() {
+      let () = self.popped.notify_one();
+    }
+    elem
+  }
+
+  /// Pop the oldest element from the ring buffer, blocking for at most
+  /// `timeout` until one is available if it is empty.
+  ///
+  /// `None` is returned if the timeout elapsed or the ring buffer is
+  /// both closed and empty.
+  pub fn pop_timeout(&self, timeout: Duration) -> Option<T> {
+    let state = self.lock();
+    let (mut state, _result) = self
+      .pushed
+      .wait_timeout_while(state, timeout, |state| {
+        !state.closed && state.ring.is_empty()
+      })
+      .unwrap_or_else(PoisonError::into_inner);
+    let elem = state.ring.pop_front();
+    drop(state);
+
+    if elem.is_some() {
+      let () = self.popped.notify_one();
+    }
+    elem
+  }
+
+  /// Block until the ring buffer contains at least `len` elements or it
+  /// got closed.
+  ///
+  /// The number of elements contained at the time of returning is
+  /// returned.
+  ///
+  /// # Panics
+  /// This method panics if `len` exceeds the ring buffer's capacity.
+  pub fn wait_for_len(&self, len: usize) -> usize {
+    let state = self.lock();
+    assert!(
+      len <= state.ring.capacity(),
+      "length {len} exceeds ring buffer capacity {}",
+      state.ring.capacity()
+    );
+
+    let state = self
+      .pushed
+      .wait_while(state, |state| !state.closed && state.ring.len() < len)
+      .unwrap_or_else(PoisonError::into_inner);
+    state.ring.len()
+  }
+
+  /// Close the ring buffer.
+  ///
+  /// Subsequent pushes fail, while elements already contained can still
+  /// be popped. All blocked threads are woken up.
+  pub fn close(&self) {
+    self.lock().closed = true;
+    let () = self.pushed.notify_all();
+    let () = self.popped.notify_all();
+  }
+}
@@ -0,0 +1,420 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use core::mem::size_of;
+use core::mem::MaybeUninit;
+use core::ops::Index;
+use core::ops::IndexMut;
+use core::ptr;
+use core::slice;
+
+use crate::RingIter;
+use crate::RingIterMut;
+
+
+/// Reinterpret a slice of initialized `MaybeUninit<T>` as a slice of
+/// `T`.
+///
+/// # Safety
+/// All elements of the slice have to be initialized.
+#[inline]
+unsafe fn assume_init<T>(slice: &[MaybeUninit<T>]) -> &[T] {
+  // SAFETY: `MaybeUninit<T>` is guaranteed to have the same layout as
+  //         `T` and the caller guarantees that all elements are
+  //         initialized.
+  unsafe { slice::from_raw_parts(slice.as_ptr().cast(), slice.len()) }
+}
+
+/// Reinterpret a slice of initialized `MaybeUninit<T>` as a mutable
+/// slice of `T`.
+///
+/// # Safety
+/// All elements of the slice have to be initialized.
+#[inline]
+unsafe fn assume_init_mut<T>(slice: &mut [MaybeUninit<T>]) -> &mut [T] {
+  // SAFETY: `MaybeUninit<T>` is guaranteed to have the same layout as
+  //         `T` and the caller guarantees that all elements are
+  //         initialized.
+  unsafe { slice::from_raw_parts_mut(slice.as_mut_ptr().cast(), slice.len()) }
+}
+
+
+/// A fixed-capacity ring buffer that keeps track of the number of
+/// elements it contains.
+///
+/// Contrary to [`RingBuf`][crate::RingBuf], a `BoundedRing` starts out
+/// empty and only contains the elements that have actually been pushed
+/// into it. As such, it does not require `T` to be default
+/// initializable and there is no need for `Option<T>` to model
+/// emptiness.
+///
+/// Once full, pushing an element to one end evicts the element at the
+/// other end.
+///
+/// Indexing works similar to that of
+/// [`VecDeque`][alloc::collections::VecDeque]: an index of `0` accesses
+/// the front element and an index of `self.len() - 1` the back one.
+/// Accessing an element past that panics.
+pub struct BoundedRing<T> {
+  /// Our actual data, of which `len` elements starting at `front` are
+  /// initialized.
+  data: Box<[MaybeUninit<T>]>,
+  /// The index of the front element.
+  front: usize,
+  /// The number of initialized elements.
+  len: usize,
+}
+
+impl<T> BoundedRing<T> {
+  /// Create a new, empty `BoundedRing` with the provided capacity.
+  ///
+  /// # Panics
+  /// This constructor panics if `capacity` is zero.
+  pub fn new(capacity: usize) -> Self {
+    assert_ne!(capacity, 0);
+
+    let mut vec = Vec::with_capacity(capacity);
+    vec.resize_with(capacity, MaybeUninit::uninit);
+
+    Self {
+      data: vec.into_boxed_slice(),
+      front: 0,
+      len: 0,
+    }
+  }
+
+  /// Retrieve the maximum number of elements the ring buffer can hold.
+  #[inline]
+  pub fn capacity(&self) -> usize {
+    self.data.len()
+  }
+
+  /// Retrieve the number of elements in the ring buffer.
+  #[inline]
+  pub const fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Check whether the ring buffer contains no elements.
+  #[inline]
+  pub const fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Check whether the ring buffer is filled to capacity, meaning that
+  /// the next push will evict an element.
+  #[inline]
+  pub fn is_full(&self) -> bool {
+    self.len == self.capacity()
+  }
+
+  /// Map a logical index to an index into our storage.
+  ///
+  /// The provided index has to be less than the capacity.
+  #[inline]
+  fn physical_idx(&self, idx: usize) -> usize {
+    debug_assert!(idx < self.capacity());
+
+    let idx = self.front + idx;
+    if idx >= self.capacity() {
+      idx - self.capacity()
+    } else {
+      idx
+    }
+  }
+
+  /// Retrieve a reference to the element at the provided logical index,
+  /// if any.
+  #[inline]
+  pub fn get(&self, idx: usize) -> Option<&T> {
+    if idx < self.len {
+      let idx = self.physical_idx(idx);
+      // SAFETY: All elements within `len` are initialized.
+      Some(unsafe { self.data[idx].assume_init_ref() })
+    } else {
+      None
+    }
+  }
+
+  /// Retrieve a mutable reference to the element at the provided
+  /// logical index, if any.
+  #[inline]
+  pub fn get_mut(&mut self, idx: usize) -> Option<&mut T> {
+    if idx < self.len {
+      let idx = self.physical_idx(idx);
+      // SAFETY: All elements within `len` are initialized.
+      Some(unsafe { self.data[idx].assume_init_mut() })
+    } else {
+      None
+    }
+  }
+
+  /// Retrieve the current front element, if any.
+  #[inline]
+  pub fn front(&self) -> Option<&T> {
+    self.get(0)
+  }
+
+  /// Retrieve the current front element, if any.
+  #[inline]
+  pub fn front_mut(&mut self) -> Option<&mut T> {
+    self.get_mut(0)
+  }
+
+  /// Retrieve the current back element, if any.
+  #[inline]
+  pub fn back(&self) -> Option<&T> {
+    self.get(self.len.wrapping_sub(1))
+  }
+
+  /// Retrieve the current back element, if any.
+  #[inline]
+  pub fn back_mut(&mut self) -> Option<&mut T> {
+    self.get_mut(self.len.wrapping_sub(1))
+  }
+
+  /// Push an element to the front of the ring buffer.
+  ///
+  /// If the ring buffer is full, the back element is evicted and
+  /// returned.
+  pub fn push_front(&mut self, elem: T) -> Option<T> {
+    let evicted = if self.is_full() {
+      self.pop_back()
+    } else {
+      None
+    };
+
+    self.front = self.physical_idx(self.capacity() - 1);
+    self.data[self.front] = MaybeUninit::new(elem);
+    self.len += 1;
+    evicted
+  }
+
+  /// Push an element to the back of the ring buffer.
+  ///
+  /// If the ring buffer is full, the front element is evicted and
+  /// returned.
+  pub fn push_back(&mut self, elem: T) -> Option<T> {
+    let evicted = if self.is_full() {
+      self.pop_front()
+    } else {
+      None
+    };
+
+    let idx = self.physical_idx(self.len);
+    self.data[idx] = MaybeUninit::new(elem);
+    self.len += 1;
+    evicted
+  }
+
+  /// Remove the front element from the ring buffer and return it.
+  pub fn pop_front(&mut self) -> Option<T> {
+    if self.is_empty() {
+      return None
+    }
+
+    let idx = self.front;
+    self.front = self.physical_idx(1 % self.capacity());
+    self.len -= 1;
+    // SAFETY: The element was initialized and, with `front` and `len`
+    //         adjusted, is now considered uninitialized, meaning that
+    //         it won't be read again.
+    Some(unsafe { ptr::read(self.data[idx].as_ptr()) })
+  }
+
+  /// Remove the back element from the ring buffer and return it.
+  pub fn pop_back(&mut self) -> Option<T> {
+    if self.is_empty() {
+      return None
+    }
+
+    self.len -= 1;
+    let idx = self.physical_idx(self.len);
+    // SAFETY: The element was initialized and, with `len` adjusted, is
+    //         now considered uninitialized, meaning that it won't be
+    //         read again.
+    Some(unsafe { ptr::read(self.data[idx].as_ptr()) })
+  }
+
+  /// Remove all elements from the ring buffer.
+  pub fn clear(&mut self) {
+    let (front, back) = self.as_mut_slices();
+    let front = front as *mut [T];
+    let back = back as *mut [T];
+
+    self.front = 0;
+    self.len = 0;
+
+    // SAFETY: Both slices were initialized and are now considered
+    //         uninitialized, because we reset `len` above. Doing so
+    //         before dropping the elements makes sure that a panicking
+    //         `Drop` impl can't cause a double drop.
+    unsafe {
+      let () = ptr::drop_in_place(front);
+      let () = ptr::drop_in_place(back);
+    }
+  }
+
+  /// Retrieve the contents of the ring buffer as a pair of slices.
+  ///
+  /// The first slice starts with the front element, the second one ends
+  /// with the back element. Either of them may be empty.
+  pub fn as_slices(&self) -> (&[T], &[T]) {
+    let (front, back) = if self.front + self.len <= self.capacity() {
+      (&self.data[self.front..self.front + self.len], &self.data[..0])
+    } else {
+      let end = self.front + self.len - self.capacity();
+      (&self.data[self.front..], &self.data[..end])
+    };
+
+    // SAFETY: Both slices cover only initialized elements.
+    unsafe { (assume_init(front), assume_init(back)) }
+  }
+
+  /// Retrieve the contents of the ring buffer as a pair of mutable
+  /// slices.
+  ///
+  /// The first slice starts with the front element, the second one ends
+  /// with the back element. Either of them may be empty.
+  pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+    let front = self.front;
+    let len = self.len;
+    let cap = self.capacity();
+    let (back, front) = self.data.split_at_mut(front);
+
+    let (front, back) = if front.len() >= len {
+      (&mut front[..len], &mut back[..0])
+    } else {
+      let end = len - front.len();
+      (front, &mut back[..end])
+    };
+
+    debug_assert!(front.len() + back.len() <= cap);
+    // SAFETY: Both slices cover only initialized elements.
+    unsafe { (assume_init_mut(front), assume_init_mut(back)) }
+  }
+
+  /// Rearrange the internal storage of the ring buffer so that all its
+  /// elements are in one contiguous slice, with the front being the
+  /// first element and the back the last one.
+  pub fn make_contiguous(&mut self) -> &mut [T] {
+    let () = self.data.rotate_left(self.front);
+    self.front = 0;
+    // SAFETY: After the rotation the first `len` elements are the
+    //         initialized ones.
+    unsafe { assume_init_mut(&mut self.data[..self.len]) }
+  }
+
+  /// Retrieve an iterator over the elements of the ring buffer.
+  ///
+  /// The iterator traverses the ring buffer in front-to-back manner.
+  #[inline]
+  pub fn iter(&self) -> RingIter<'_, T> {
+    // SAFETY: The `len` elements starting at `front` are initialized
+    //         and remain borrowed for the lifetime of the iterator.
+    unsafe {
+      RingIter::from_raw_parts(
+        self.data.as_ptr().cast(),
+        self.capacity(),
+        self.front,
+        self.len,
+      )
+    }
+  }
+
+  /// Retrieve a mutating iterator over the elements of the ring buffer.
+  ///
+  /// The iterator traverses the ring buffer in front-to-back manner.
+  ///
+  /// # Panics
+  /// This method panics when `T` is a zero sized type.
+  #[inline]
+  pub fn iter_mut(&mut self) -> RingIterMut<'_, T> {
+    assert_ne!(
+      size_of::<T>(),
+      0,
+      "Mutable iterators are not supported on ring buffers over zero sized types"
+    );
+
+    let cap = self.capacity();
+    // SAFETY: The `len` elements starting at `front` are initialized
+    //         and remain exclusively borrowed for the lifetime of the
+    //         iterator.
+    unsafe {
+      RingIterMut::from_raw_parts(
+        self.data.as_mut_ptr().cast(),
+        cap,
+        self.front,
+        self.len,
+      )
+    }
+  }
+}
+
+impl<T> Clone for BoundedRing<T>
+where
+  T: Clone,
+{
+  fn clone(&self) -> Self {
+    let mut other = Self::new(self.capacity());
+    let () = self.iter().cloned().for_each(|elem| {
+      let _evicted = other.push_back(elem);
+    });
+    other
+  }
+}
+
+impl<T> Debug for BoundedRing<T>
+where
+  T: Debug,
+{
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.debug_list().entries(self.iter()).finish()
+  }
+}
+
+impl<T> Drop for BoundedRing<T> {
+  fn drop(&mut self) {
+    let () = self.clear();
+  }
+}
+
+/// Two `BoundedRing` objects are considered equal if they contain the
+/// same elements in the same order, regardless of their capacity.
+impl<T> PartialEq for BoundedRing<T>
+where
+  T: PartialEq,
+{
+  fn eq(&self, other: &Self) -> bool {
+    self.len == other.len && self.iter().eq(other.iter())
+  }
+}
+
+impl<T> Eq for BoundedRing<T> where T: Eq {}
+
+impl<T> Index<usize> for BoundedRing<T> {
+  type Output = T;
+
+  #[inline]
+  fn index(&self, idx: usize) -> &Self::Output {
+    let len = self.len;
+    self
+      .get(idx)
+      .unwrap_or_else(|| panic!("index out of bounds: the len is {len} but the index is {idx}"))
+  }
+}
+
+impl<T> IndexMut<usize> for BoundedRing<T> {
+  #[inline]
+  fn index_mut(&mut self, idx: usize) -> &mut Self::Output {
+    let len = self.len;
+    self
+      .get_mut(idx)
+      .unwrap_or_else(|| panic!("index out of bounds: the len is {len} but the index is {idx}"))
+  }
+}
@@ -0,0 +1,180 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+//! A ring buffer with a single writer and many independent readers.
+//!
+//! A [`BroadcastRing`] retains the most recently pushed elements. Each
+//! [`Reader`] is a cursor into the ring buffer's stream of elements
+//! and advances at its own pace. Readers do not borrow the ring
+//! buffer, so any number of them can be kept around while elements
+//! are being pushed.
+//!
+//! If the writer overwrites elements a reader has not yet seen, the
+//! reader is notified by means of a [`Lagged`] error and moved to the
+//! oldest element still available.
+//!
+//! # Examples
+//! ```rust
+//! use rbuf::broadcast::BroadcastRing;
+//! use rbuf::broadcast::Lagged;
+//!
+//! let mut ring = BroadcastRing::new(2);
+//! let mut fast = ring.reader();
+//! let mut slow = ring.reader();
+//!
+//! let _evicted = ring.push(1);
+//! assert_eq!(ring.read(&mut fast), Ok(Some(&1)));
+//! let _evicted = ring.push(2);
+//! let _evicted = ring.push(3);
+//! assert_eq!(ring.read(&mut fast), Ok(Some(&2)));
+//! assert_eq!(ring.read(&mut fast), Ok(Some(&3)));
+//! assert_eq!(ring.read(&mut fast), Ok(None));
+//!
+//! assert_eq!(ring.read(&mut slow), Err(Lagged(1)));
+//! assert_eq!(ring.read(&mut slow), Ok(Some(&2)));
+//! ```
+
+use core::fmt::Display;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use crate::BoundedRing;
+
+
+/// The error reported to a [`Reader`] that fell behind the writer.
+///
+/// The contained value is the number of elements the reader missed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Lagged(pub u64);
+
+impl Display for Lagged {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    write!(f, "reader lagged behind by {} elements", self.0)
+  }
+}
+
+#[cfg(feature = "std")]
+impl Error for Lagged {}
+
+
+/// A cursor into the stream of elements of a [`BroadcastRing`].
+///
+/// A reader is only meaningful in conjunction with the ring buffer it
+/// was created from.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Reader {
+  /// The sequence number of the next element to read.
+  next: u64,
+}
+
+
+/// A ring buffer retaining the most recently pushed elements for
+/// consumption by any number of [`Reader`]s.
+#[derive(Clone, Debug)]
+pub struct BroadcastRing<T> {
+  /// The retained elements.
+  ring: BoundedRing<T>,
+  /// The sequence number the next pushed element will receive.
+  seq: u64,
+}
+
+impl<T> BroadcastRing<T> {
+  /// Create a new, empty `BroadcastRing` retaining up to `capacity`
+  /// elements.
+  ///
+  /// # Panics
+  /// This constructor panics if `capacity` is zero.
+  pub fn new(capacity: usize) -> Self {
+    Self {
+      ring: BoundedRing::new(capacity),
+      seq: 0,
+    }
+  }
+
+  /// Retrieve the maximum number of retained elements.
+  #[inline]
+  pub fn capacity(&self) -> usize {
+    self.ring.capacity()
+  }
+
+  /// Retrieve the number of retained elements.
+  #[inline]
+  pub const fn len(&self) -> usize {
+    self.ring.len()
+  }
+
+  /// Check whether the ring buffer has no retained elements.
+  #[inline]
+  pub const fn is_empty(&self) -> bool {
+    self.ring.is_empty()
+  }
+
+  /// Retrieve the sequence number of the oldest retained element.
+  #[inline]
+  fn oldest(&self) -> u64 {
+    self.seq - self.ring.len() as u64
+  }
+
+  /// Push an element into the ring buffer, making it available to all
+  /// readers.
+  ///
+  /// If the ring buffer is full, the oldest element is evicted and
+  /// returned.
+  #[inline]
+  pub fn push(&mut self, elem: T) -> Option<T> {
+    self.seq += 1;
+    self.ring.push_back(elem)
+  }
+
+  /// Create a new [`Reader`] that observes all elements pushed from now
+  /// on.
+  #[inline]
+  pub fn reader(&self) -> Reader {
+    Reader { next: self.seq }
+  }
+
+  /// Create a new [`Reader`] that observes all currently retained
+  /// elements, as well as those pushed from now on.
+  #[inline]
+  pub fn reader_from_oldest(&self) -> Reader {
+    Reader {
+      next: self.oldest(),
+    }
+  }
+
+  /// Retrieve the number of elements `reader` has yet to read.
+  ///
+  /// Elements that the reader missed because it lagged behind are not
+  /// included.
+  #[inline]
+  pub fn pending(&self, reader: &Reader) -> usize {
+    let next = reader.next.clamp(self.oldest(), self.seq);
+    (self.seq - next) as usize
+  }
+
+  /// Read the next element on behalf of `reader`, advancing it.
+  ///
+  /// `Ok(None)` is returned if the reader has seen all elements pushed
+  /// so far. If elements the reader has not yet seen were evicted, a
+  /// [`Lagged`] error with the number of missed elements is returned
+  /// and the reader is moved to the oldest retained element, from
+  /// which the next read continues.
+  pub fn read(&self, reader: &mut Reader) -> Result<Option<&T>, Lagged> {
+    let oldest = self.oldest();
+    if reader.next < oldest {
+      let missed = oldest - reader.next;
+      reader.next = oldest;
+      return Err(Lagged(missed))
+    }
+
+    if reader.next >= self.seq {
+      return Ok(None)
+    }
+
+    let idx = (reader.next - oldest) as usize;
+    reader.next += 1;
+    Ok(self.ring.get(idx))
+  }
+}
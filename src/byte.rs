@@ -0,0 +1,353 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "std")]
+use std::io::BufRead;
+#[cfg(feature = "std")]
+use std::io::IoSlice;
+#[cfg(feature = "std")]
+use std::io::IoSliceMut;
+#[cfg(feature = "std")]
+use std::io::Read;
+#[cfg(feature = "std")]
+use std::io::Write;
+
+#[cfg(feature = "bytes")]
+use bytes::buf::UninitSlice;
+#[cfg(feature = "bytes")]
+use bytes::Buf;
+#[cfg(feature = "bytes")]
+use bytes::BufMut;
+
+use crate::RingBuf;
+
+
+/// A ring buffer of bytes with separate read and write positions.
+///
+/// Contrary to a [`RingBuf<u8>`][RingBuf], which is always full, a
+/// `ByteRing` distinguishes between readable bytes, which have been
+/// written but not yet read, and free space available for writing. As
+/// such, it can serve as a fixed-size in-memory pipe.
+///
+/// When the `std` feature is enabled, `ByteRing` implements
+/// `io::Read`, `io::Write`, and `io::BufRead`. When the `bytes` feature
+/// is enabled, it implements the `bytes` crate's `Buf` and `BufMut`
+/// traits, so that it can be used with codecs and framing utilities
+/// directly.
+///
+/// # Examples
+/// ```rust
+/// # use rbuf::ByteRing;
+/// let mut ring = ByteRing::new(8);
+/// assert_eq!(ring.push_slice(b"hello"), 5);
+/// assert_eq!(ring.push_slice(b" world"), 3);
+///
+/// let mut buf = [0; 4];
+/// assert_eq!(ring.pop_slice(&mut buf), 4);
+/// assert_eq!(&buf, b"hell");
+/// assert_eq!(ring.as_slices(), (&b"o wo"[..], &b""[..]));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ByteRing {
+  /// Our actual data. The front of the ring buffer marks the read
+  /// position.
+  buf: RingBuf<u8>,
+  /// The number of readable bytes, starting at the read position.
+  len: usize,
+}
+
+impl ByteRing {
+  /// Create a new, empty `ByteRing` with capacity for `capacity` bytes.
+  ///
+  /// # Panics
+  /// This constructor panics if `capacity` is zero.
+  pub fn new(capacity: usize) -> Self {
+    Self {
+      buf: RingBuf::new(capacity),
+      len: 0,
+    }
+  }
+
+  /// Retrieve the ring buffer's capacity.
+  #[inline]
+  pub fn capacity(&self) -> usize {
+    self.buf.len()
+  }
+
+  /// Retrieve the number of readable bytes.
+  #[inline]
+  pub const fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Check whether the ring buffer contains no readable bytes.
+  #[inline]
+  pub const fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Check whether the ring buffer has no free space left.
+  #[inline]
+  pub fn is_full(&self) -> bool {
+    self.len == self.capacity()
+  }
+
+  /// Retrieve the number of bytes that can be written before the ring
+  /// buffer is full.
+  #[inline]
+  pub fn free(&self) -> usize {
+    self.capacity() - self.len
+  }
+
+  /// Retrieve the readable bytes as a pair of slices.
+  ///
+  /// The first slice starts with the oldest byte, the second one ends
+  /// with the most recently written one. Either of them may be empty.
+  #[inline]
+  pub fn as_slices(&self) -> (&[u8], &[u8]) {
+    self.buf.range_slices(0..self.len)
+  }
+
+  /// Retrieve the readable bytes as a pair of mutable slices.
+  ///
+  /// The first slice starts with the oldest byte, the second one ends
+  /// with the most recently written one. Either of them may be empty.
+  #[inline]
+  pub fn as_mut_slices(&mut self) -> (&mut [u8], &mut [u8]) {
+    self.buf.range_slices_mut(0..self.len)
+  }
+
+  /// Retrieve the free space as a pair of mutable slices.
+  ///
+  /// Bytes written to the slices only become readable once
+  /// [committed][ByteRing::commit]. Either of the slices may be empty.
+  #[inline]
+  pub fn spare_slices_mut(&mut self) -> (&mut [u8], &mut [u8]) {
+    let cap = self.capacity();
+    self.buf.range_slices_mut(self.len..cap)
+  }
+
+  /// Mark `n` bytes of the free space as readable, after they have been
+  /// written through [`ByteRing::spare_slices_mut`].
+  ///
+  /// # Panics
+  /// This method panics if `n` exceeds the free space.
+  #[inline]
+  pub fn commit(&mut self, n: usize) {
+    assert!(n <= self.free(), "cannot commit {n} bytes with only {} free", self.free());
+    self.len += n;
+  }
+
+  /// Discard `n` readable bytes, after they have been read through
+  /// [`ByteRing::as_slices`].
+  ///
+  /// # Panics
+  /// This method panics if `n` exceeds the number of readable bytes.
+  #[inline]
+  pub fn consume(&mut self, n: usize) {
+    assert!(n <= self.len, "cannot consume {n} bytes with only {} readable", self.len);
+    let () = self.buf.rotate_left(n);
+    self.len -= n;
+  }
+
+  /// Discard all readable bytes.
+  #[inline]
+  pub fn clear(&mut self) {
+    self.consume(self.len)
+  }
+
+  /// Write as many bytes from `data` as fit into the free space.
+  ///
+  /// The number of bytes written is returned.
+  pub fn push_slice(&mut self, data: &[u8]) -> usize {
+    let (first, second) = self.spare_slices_mut();
+    let n1 = first.len().min(data.len());
+    let () = first[..n1].copy_from_slice(&data[..n1]);
+    let n2 = second.len().min(data.len() - n1);
+    let () = second[..n2].copy_from_slice(&data[n1..n1 + n2]);
+
+    let () = self.commit(n1 + n2);
+    n1 + n2
+  }
+
+  /// Read as many readable bytes into `buf` as fit.
+  ///
+  /// The number of bytes read is returned.
+  pub fn pop_slice(&mut self, buf: &mut [u8]) -> usize {
+    let (first, second) = self.as_slices();
+    let n1 = first.len().min(buf.len());
+    let () = buf[..n1].copy_from_slice(&first[..n1]);
+    let n2 = second.len().min(buf.len() - n1);
+    let () = buf[n1..n1 + n2].copy_from_slice(&second[..n2]);
+
+    let () = self.consume(n1 + n2);
+    n1 + n2
+  }
+
+  /// Retrieve the readable bytes as a pair of [`IoSlice`]s, suitable
+  /// for [`Write::write_vectored`].
+  #[cfg(feature = "std")]
+  #[inline]
+  pub fn io_slices(&self) -> [IoSlice<'_>; 2] {
+    let (first, second) = self.as_slices();
+    [IoSlice::new(first), IoSlice::new(second)]
+  }
+
+  /// Retrieve the free space as a pair of [`IoSliceMut`]s, suitable for
+  /// [`Read::read_vectored`].
+  ///
+  /// Bytes written to the slices only become readable once
+  /// [committed][ByteRing::commit].
+  #[cfg(feature = "std")]
+  #[inline]
+  pub fn io_slices_mut(&mut self) -> [IoSliceMut<'_>; 2] {
+    let (first, second) = self.spare_slices_mut();
+    [IoSliceMut::new(first), IoSliceMut::new(second)]
+  }
+
+  /// Fill the free space with data read from `reader`.
+  ///
+  /// The data is read directly into the ring buffer, using vectored
+  /// I/O to cover both free regions with a single call. The number of
+  /// bytes read is returned.
+  #[cfg(feature = "std")]
+  pub fn read_from<R>(&mut self, reader: &mut R) -> io::Result<usize>
+  where
+    R: Read + ?Sized,
+  {
+    let n = reader.read_vectored(&mut self.io_slices_mut())?;
+    let () = self.commit(n);
+    Ok(n)
+  }
+
+  /// Drain readable bytes into `writer`.
+  ///
+  /// The data is written directly from the ring buffer, using vectored
+  /// I/O to cover both readable regions with a single call. The number
+  /// of bytes written is returned.
+  #[cfg(feature = "std")]
+  pub fn write_to<W>(&mut self, writer: &mut W) -> io::Result<usize>
+  where
+    W: Write + ?Sized,
+  {
+    let n = writer.write_vectored(&self.io_slices())?;
+    let () = self.consume(n);
+    Ok(n)
+  }
+}
+
+/// Two `ByteRing` objects are considered equal if they contain the same
+/// readable bytes, regardless of their capacity or position in storage.
+impl PartialEq for ByteRing {
+  fn eq(&self, other: &Self) -> bool {
+    let (first, second) = self.as_slices();
+    let (other_first, other_second) = other.as_slices();
+
+    self.len == other.len
+      && first
+        .iter()
+        .chain(second)
+        .eq(other_first.iter().chain(other_second))
+  }
+}
+
+impl Eq for ByteRing {}
+
+#[cfg(feature = "std")]
+impl Read for ByteRing {
+  #[inline]
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    Ok(self.pop_slice(buf))
+  }
+}
+
+#[cfg(feature = "std")]
+impl BufRead for ByteRing {
+  #[inline]
+  fn fill_buf(&mut self) -> io::Result<&[u8]> {
+    let (first, second) = self.as_slices();
+    if first.is_empty() {
+      Ok(second)
+    } else {
+      Ok(first)
+    }
+  }
+
+  #[inline]
+  fn consume(&mut self, amt: usize) {
+    ByteRing::consume(self, amt)
+  }
+}
+
+/// Writing to a `ByteRing` never blocks. Once full, writes return
+/// `Ok(0)`.
+#[cfg(feature = "std")]
+impl Write for ByteRing {
+  #[inline]
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    Ok(self.push_slice(buf))
+  }
+
+  #[inline]
+  fn flush(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+}
+
+#[cfg(feature = "bytes")]
+impl Buf for ByteRing {
+  #[inline]
+  fn remaining(&self) -> usize {
+    self.len
+  }
+
+  #[inline]
+  fn chunk(&self) -> &[u8] {
+    // The first slice is only ever empty if there is nothing to read.
+    self.as_slices().0
+  }
+
+  #[inline]
+  fn advance(&mut self, cnt: usize) {
+    ByteRing::consume(self, cnt)
+  }
+
+  #[cfg(feature = "std")]
+  fn chunks_vectored<'slf>(&'slf self, dst: &mut [IoSlice<'slf>]) -> usize {
+    let (first, second) = self.as_slices();
+    let mut n = 0;
+    for (slice, dst) in [first, second]
+      .into_iter()
+      .filter(|slice| !slice.is_empty())
+      .zip(dst)
+    {
+      *dst = IoSlice::new(slice);
+      n += 1;
+    }
+    n
+  }
+}
+
+// SAFETY: `advance_mut` only ever marks bytes as readable that are
+//         part of the free space handed out by `chunk_mut`, and all of
+//         our storage is initialized anyway.
+#[cfg(feature = "bytes")]
+unsafe impl BufMut for ByteRing {
+  #[inline]
+  fn remaining_mut(&self) -> usize {
+    self.free()
+  }
+
+  #[inline]
+  unsafe fn advance_mut(&mut self, cnt: usize) {
+    self.commit(cnt)
+  }
+
+  #[inline]
+  fn chunk_mut(&mut self) -> &mut UninitSlice {
+    // The first slice is only ever empty if there is no free space.
+    UninitSlice::new(self.spare_slices_mut().0)
+  }
+}
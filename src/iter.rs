@@ -1,11 +1,9 @@
 // Copyright (C) 2021-2025 Daniel Mueller <deso@posteo.net>
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-use std::iter::DoubleEndedIterator;
-use std::iter::FusedIterator;
-use std::marker::PhantomData;
-use std::ops::Index;
-use std::ops::IndexMut;
+use core::iter::DoubleEndedIterator;
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
 
 
 macro_rules! iterator {
@@ -14,18 +12,22 @@ macro_rules! iterator {
     {$( $const_:tt )?},
     {$( $ref_mut:tt )?},
     {$ptr_mut:tt},
-    {$idx:path},
   ) => {
     $(#[$meta])*
     pub struct $name<'b, T> {
-      /// A pointer to the ring buffer we work with.
+      /// A pointer to the first element of the ring buffer's underlying
+      /// storage.
       ///
       /// We use a pointer here, because at least for mutable iterators,
       /// the borrow checker is unable to prove correct adherence to
       /// aliasing rules, because we yield elements with 'b lifetime
       /// that outlives 'self. We make sure to guarantee those at
       /// runtime.
-      buf: *$ptr_mut $crate::RingBuf<T>,
+      data: *$ptr_mut T,
+      /// The number of slots in the underlying storage.
+      cap: usize,
+      /// The storage index of the front element.
+      front: usize,
       /// The index of the next element to yield in forward direction.
       next: usize,
       /// The index of the next element to yield in backward direction.
@@ -35,19 +37,49 @@ macro_rules! iterator {
     }
 
     impl<'b, T> $name<'b, T> {
-      /// Create a new iterator over the given ring buffer data.
+      /// Create a new iterator over `len` elements of ring buffer
+      /// storage, starting at the element at index `front`.
+      ///
+      /// # Safety
+      /// `data` has to point to `cap` slots of storage that remain
+      /// valid (and, for mutable iterators, exclusively borrowed) for
+      /// the lifetime `'b`. The `len` logical elements starting at
+      /// `front` (and wrapping around at `cap`) have to be initialized.
       #[inline]
-      pub(crate) $( $const_ )? fn new(buf: &'b $( $ref_mut )? $crate::RingBuf<T>) -> Self {
-        let len = buf.len();
+      pub(crate) $( $const_ )? unsafe fn from_raw_parts(
+        data: *$ptr_mut T,
+        cap: usize,
+        front: usize,
+        len: usize,
+      ) -> Self {
+        debug_assert!(len <= cap);
+        debug_assert!(front < cap || cap == 0);
+
         Self {
-          buf: buf as _,
-          // Indexing into a `RingBuf` at zero always yields the front
-          // and that's where we start.
+          data,
+          cap,
+          front,
+          // Logical index zero always refers to the front and that's
+          // where we start.
           next: 0,
           next_back: len,
           _phantom: PhantomData,
         }
       }
+
+      /// Retrieve a pointer to the element at logical index `idx`.
+      #[inline]
+      fn ptr(&self, idx: usize) -> *$ptr_mut T {
+        // `front` is always less than `cap` and so is `idx`, meaning
+        // that a single subtraction suffices for wrapping around.
+        let idx = self.front + idx;
+        let idx = if idx >= self.cap { idx - self.cap } else { idx };
+        debug_assert!(idx < self.cap, "idx: {idx}, cap: {}", self.cap);
+
+        // SAFETY: The index is within the bounds of the underlying
+        //         storage.
+        unsafe { self.data.add(idx) }
+      }
     }
 
     impl<'b, T> Iterator for $name<'b, T> {
@@ -59,13 +91,12 @@ macro_rules! iterator {
           let idx = self.next;
           self.next += 1;
 
-          // SAFETY: Our `buf` pointer is always valid. For mutable
+          // SAFETY: Our `data` pointer is always valid. For mutable
           //         iterators, we guarantee that we never yield a
           //         mutable reference to the same element with a
           //         lifetime outliving `self` twice, by stopping
           //         iteration before that.
-          let rbuf = unsafe { &$( $ref_mut )?*self.buf };
-          Some($idx(rbuf, idx))
+          Some(unsafe { &$( $ref_mut )?*self.ptr(idx) })
         } else {
           None
         }
@@ -90,13 +121,12 @@ macro_rules! iterator {
           debug_assert!(self.next_back > 0);
           self.next_back -= 1;
 
-          // SAFETY: Our `buf` pointer is always valid. For mutable
+          // SAFETY: Our `data` pointer is always valid. For mutable
           //         iterators, we guarantee that we never yield a
           //         mutable reference to the same element with a
           //         lifetime outliving `self` twice, by stopping
           //         iteration before that.
-          let rbuf = unsafe { &$( $ref_mut )?*self.buf };
-          Some($idx(rbuf, self.next_back))
+          Some(unsafe { &$( $ref_mut )?*self.ptr(self.next_back) })
         } else {
           None
         }
@@ -110,16 +140,16 @@ macro_rules! iterator {
 }
 
 iterator! {
-  /// An iterator over the elements of a `RingBuf`.
+  /// An iterator over the elements of a ring buffer.
   ///
   /// Iteration happens front-to-back, unless reversed.
   #[derive(Copy, Clone, Debug, Eq, PartialEq)]
-  struct RingIter, {const}, {}, {const}, {Index::index},
+  struct RingIter, {const}, {}, {const},
 }
 iterator! {
-  /// A mutable iterator over the elements of a `RingBuf`.
+  /// A mutable iterator over the elements of a ring buffer.
   ///
   /// Iteration happens front-to-back, unless reversed.
   #[derive(Debug, Eq, PartialEq)]
-  struct RingIterMut, {}, {mut}, {mut}, {IndexMut::index_mut},
+  struct RingIterMut, {}, {mut}, {mut},
 }
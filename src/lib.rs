@@ -3,13 +3,101 @@
 
 //! A library providing a general purpose ring buffer implementation
 //! with some non-standard constraints.
+//!
+//! The crate is `no_std` compatible, requiring only `alloc`, when the
+//! default `std` feature is disabled. The lock-free ring buffers (i.e.,
+//! those of the `spsc` and `seqlock` modules as well as `MpmcRing`)
+//! additionally require atomic compare-and-swap operations on pointer
+//! sized values and are not available on targets lacking them.
 
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "allocator-api2")]
+mod allocator;
+#[cfg(feature = "futures")]
+pub mod async_channel;
+mod bit;
+mod bits;
+#[cfg(feature = "std")]
+mod blocking;
+mod bounded;
+pub mod broadcast;
+mod byte;
 mod iter;
+#[cfg(all(feature = "mirrored", target_os = "linux"))]
+mod mirrored;
+#[cfg(feature = "mmap")]
+mod mmap;
+#[cfg(target_has_atomic = "ptr")]
+mod mpmc;
+mod option;
+#[cfg(feature = "tokio")]
+pub mod pipe;
+mod pow2;
+#[cfg(feature = "record-log")]
+mod record_log;
+mod record_ring;
 mod ring;
+#[cfg(target_has_atomic = "ptr")]
+pub mod seqlock;
+#[cfg(all(feature = "shm", target_os = "linux"))]
+pub mod shm;
+mod sparse;
+#[cfg(target_has_atomic = "ptr")]
+pub mod spsc;
+mod storage;
+#[cfg(target_has_atomic = "ptr")]
+mod sync;
+#[cfg(all(any(feature = "mirrored", feature = "shm"), target_os = "linux"))]
+mod sys;
+mod tail;
+mod util;
 
+pub use bit::BitIter;
+pub use bit::BitRingBuf;
+#[cfg(feature = "std")]
+pub use blocking::Closed;
+#[cfg(feature = "std")]
+pub use blocking::SyncRingBuf;
+pub use bounded::BoundedRing;
+pub use byte::ByteRing;
 pub use iter::RingIter;
 pub use iter::RingIterMut;
+#[cfg(all(feature = "mirrored", target_os = "linux"))]
+pub use mirrored::MirroredRingBuf;
+#[cfg(feature = "mmap")]
+pub use mmap::MmapRingBuf;
+#[cfg(target_has_atomic = "ptr")]
+pub use mpmc::MpmcRing;
+pub use option::SomeIter;
+pub use option::TakeAll;
+pub use pow2::Pow2;
+pub use pow2::Pow2RingBuf;
+#[cfg(feature = "record-log")]
+pub use record_log::Record;
+#[cfg(feature = "record-log")]
+pub use record_log::RecordIter;
+#[cfg(feature = "record-log")]
+pub use record_log::RecordLog;
+pub use record_ring::RecordRing;
+pub use record_ring::RecordRingIter;
+pub use ring::RingArray;
 pub use ring::RingBuf;
+pub use sparse::SparseIter;
+pub use sparse::SparseIterMut;
+pub use sparse::SparseRingBuf;
+pub use storage::Storage;
+pub use tail::TailWriter;
+
+
+#[doc(hidden)]
+pub mod __private {
+  pub use alloc::vec;
+}
 
 
 /// Create a [`RingBuf`] containing the provided elements.
@@ -28,9 +116,9 @@ pub use ring::RingBuf;
 #[macro_export]
 macro_rules! ring_buf [
   ($($x:expr), *) => {
-    ::rbuf::RingBuf::from_vec(::std::vec![$($x),*])
+    ::rbuf::RingBuf::from_vec(::rbuf::__private::vec![$($x),*])
   };
   ($($x:expr,) *) => {
-    ::rbuf::RingBuf::from_vec(::std::vec![$($x),*])
+    ::rbuf::RingBuf::from_vec(::rbuf::__private::vec![$($x),*])
   };
 ];
@@ -0,0 +1,321 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use core::fmt::Debug;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use core::mem::size_of;
+use core::ops::Index;
+use core::ops::IndexMut;
+use core::ptr;
+use core::ptr::NonNull;
+use core::slice;
+use core::sync::atomic::compiler_fence;
+use core::sync::atomic::Ordering;
+use std::io;
+use std::os::raw::c_int;
+
+use crate::sys::check;
+
+
+/// Calculate the greatest common divisor of `a` and `b`.
+fn gcd(mut a: usize, mut b: usize) -> usize {
+  while b != 0 {
+    (a, b) = (b, a % b);
+  }
+  a
+}
+
+
+/// A file descriptor that gets closed when dropped.
+struct Fd(c_int);
+
+impl Drop for Fd {
+  fn drop(&mut self) {
+    // SAFETY: We own the file descriptor and it is valid.
+    let _rc = unsafe { libc::close(self.0) };
+  }
+}
+
+
+/// Map `size` bytes of anonymous shared memory twice, back-to-back.
+///
+/// On success, a pointer to the start of the `2 * size` bytes large
+/// region is returned. `size` has to be a multiple of the page size.
+fn map_mirrored(size: usize) -> io::Result<NonNull<u8>> {
+  let name = b"rbuf\0";
+  // SAFETY: The name is a valid NUL terminated string.
+  let fd = check(unsafe { libc::memfd_create(name.as_ptr().cast(), libc::MFD_CLOEXEC) })?;
+  let fd = Fd(fd);
+  let len =
+    libc::off_t::try_from(size).map_err(|_err| io::Error::from(io::ErrorKind::OutOfMemory))?;
+  // SAFETY: The file descriptor is valid.
+  let _rc = check(unsafe { libc::ftruncate(fd.0, len) })?;
+
+  // Reserve an address range large enough for both mappings, so that
+  // we can subsequently place them without racing with other mappings.
+  // SAFETY: We request a new, private mapping without any access.
+  let addr = unsafe {
+    libc::mmap(
+      ptr::null_mut(),
+      2 * size,
+      libc::PROT_NONE,
+      libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+      -1,
+      0,
+    )
+  };
+  if addr == libc::MAP_FAILED {
+    return Err(io::Error::last_os_error())
+  }
+
+  for offset in [0, size] {
+    // SAFETY: The target range is part of the reservation we own,
+    //         which `MAP_FIXED` replaces.
+    let rc = unsafe {
+      libc::mmap(
+        addr.cast::<u8>().add(offset).cast(),
+        size,
+        libc::PROT_READ | libc::PROT_WRITE,
+        libc::MAP_SHARED | libc::MAP_FIXED,
+        fd.0,
+        0,
+      )
+    };
+    if rc == libc::MAP_FAILED {
+      let err = io::Error::last_os_error();
+      // SAFETY: We own the entire region.
+      let _rc = unsafe { libc::munmap(addr, 2 * size) };
+      return Err(err)
+    }
+  }
+
+  // The file descriptor is no longer needed, as the mappings keep the
+  // memory alive.
+  drop(fd);
+  // SAFETY: `mmap` never hands out a null pointer on success.
+  Ok(unsafe { NonNull::new_unchecked(addr.cast()) })
+}
+
+
+/// A ring buffer whose contents are always accessible as a single,
+/// contiguous slice.
+///
+/// The ring buffer's memory is mapped twice into the address space,
+/// back-to-back, so that accessing past the end of the first mapping
+/// transparently wraps around to its start. As a result, the ring
+/// buffer's contents, starting at the front, are always available as a
+/// slice, without the need to [rearrange][crate::RingBuf::make_contiguous]
+/// them.
+///
+/// Similar to [`RingBuf`][crate::RingBuf], the ring buffer is always
+/// "full" and new elements overwrite the oldest ones. Because memory
+/// can only be mapped at page granularity, the ring buffer's length
+/// may be larger than requested.
+///
+/// This type is only available on Linux.
+///
+/// # Examples
+/// ```rust
+/// # use rbuf::MirroredRingBuf;
+/// let mut buf = MirroredRingBuf::<u32>::new(4).unwrap();
+/// let len = buf.len();
+/// for i in 0..len + 2 {
+///   let () = buf.push_back(i as u32);
+/// }
+///
+/// let slice = buf.as_slice();
+/// assert_eq!(slice.len(), len);
+/// assert_eq!(slice[0], 2);
+/// assert_eq!(slice[len - 1], len as u32 + 1);
+/// ```
+pub struct MirroredRingBuf<T> {
+  /// The start of the first of the two mappings.
+  ptr: NonNull<T>,
+  /// The number of elements in the ring buffer.
+  len: usize,
+  /// The index of the front element.
+  front: usize,
+}
+
+// SAFETY: We exclusively own our memory, just like a `Box<[T]>`.
+unsafe impl<T> Send for MirroredRingBuf<T> where T: Send {}
+// SAFETY: We exclusively own our memory, just like a `Box<[T]>`.
+unsafe impl<T> Sync for MirroredRingBuf<T> where T: Sync {}
+
+impl<T> MirroredRingBuf<T>
+where
+  T: Copy + Default,
+{
+  /// Create a new `MirroredRingBuf` of at least `len` elements, all
+  /// set to their default value.
+  ///
+  /// # Panics
+  /// This constructor panics if `len` is zero or `T` is zero-sized.
+  pub fn new(len: usize) -> io::Result<Self> {
+    assert_ne!(len, 0);
+    let size = size_of::<T>();
+    assert_ne!(size, 0, "zero-sized types are not supported");
+
+    // SAFETY: `sysconf` is always safe to call.
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    let page_size = usize::try_from(page_size).map_err(|_err| io::Error::last_os_error())?;
+    // The smallest number of elements that occupies an integral number
+    // of pages.
+    let unit = page_size / gcd(page_size, size);
+    let len = len
+      .checked_add(unit - 1)
+      .map(|len| len / unit * unit)
+      .filter(|len| len.checked_mul(size).and_then(|n| n.checked_mul(2)).is_some())
+      .ok_or_else(|| io::Error::from(io::ErrorKind::OutOfMemory))?;
+
+    let ptr = map_mirrored(len * size)?.cast::<T>();
+    let mut slf = Self { ptr, len, front: 0 };
+    for idx in 0..len {
+      // SAFETY: The index is in bounds of the first mapping.
+      let () = unsafe { slf.ptr.as_ptr().add(idx).write(T::default()) };
+    }
+    let () = slf.sync();
+    Ok(slf)
+  }
+}
+
+#[allow(clippy::len_without_is_empty)]
+impl<T> MirroredRingBuf<T>
+where
+  T: Copy,
+{
+  /// Inform the compiler that memory may have been modified through
+  /// the respective other mapping.
+  ///
+  /// Both mappings alias each other, which the compiler cannot know
+  /// about. Without this barrier it could, for example, reuse a value
+  /// it loaded through the second mapping after the same element got
+  /// overwritten through the first one.
+  #[inline]
+  fn sync(&mut self) {
+    compiler_fence(Ordering::SeqCst)
+  }
+
+  /// Retrieve the first mapping as a slice.
+  #[inline]
+  fn data(&self) -> &[T] {
+    // SAFETY: All `len` elements of the first mapping are valid and
+    //         initialized.
+    unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+  }
+
+  /// Retrieve the ring buffer's length.
+  #[inline]
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Retrieve the contents of the ring buffer as a single slice,
+  /// starting with the front element and ending with the back one.
+  #[inline]
+  pub fn as_slice(&self) -> &[T] {
+    // SAFETY: Starting at any index of the first mapping, `len`
+    //         elements are valid and initialized, courtesy of the
+    //         second mapping.
+    unsafe { slice::from_raw_parts(self.ptr.as_ptr().add(self.front), self.len) }
+  }
+
+  /// Retrieve the contents of the ring buffer as a single mutable
+  /// slice, starting with the front element and ending with the back
+  /// one.
+  #[inline]
+  pub fn as_mut_slice(&mut self) -> &mut [T] {
+    let () = self.sync();
+    // SAFETY: Starting at any index of the first mapping, `len`
+    //         elements are valid and initialized, courtesy of the
+    //         second mapping. None of them alias each other.
+    unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr().add(self.front), self.len) }
+  }
+
+  /// Retrieve the current front element.
+  #[inline]
+  pub fn front(&self) -> &T {
+    &self.as_slice()[0]
+  }
+
+  /// Retrieve the current back element.
+  #[inline]
+  pub fn back(&self) -> &T {
+    &self.as_slice()[self.len - 1]
+  }
+
+  /// Push an element into the ring buffer, overwriting the front
+  /// element, which is the oldest one. The element after the current
+  /// front will become the new front.
+  #[inline]
+  pub fn push_back(&mut self, elem: T) {
+    // SAFETY: The front index is in bounds of the first mapping.
+    let () = unsafe { self.ptr.as_ptr().add(self.front).write(elem) };
+    let () = self.sync();
+    self.front = (self.front + 1) % self.len;
+  }
+
+  /// Push an element into the ring buffer, overwriting the back
+  /// element, which is the newest one. The element will become the new
+  /// front.
+  #[inline]
+  pub fn push_front(&mut self, elem: T) {
+    let idx = self.front.checked_sub(1).unwrap_or(self.len - 1);
+    // SAFETY: The index is in bounds of the first mapping.
+    let () = unsafe { self.ptr.as_ptr().add(idx).write(elem) };
+    let () = self.sync();
+    self.front = idx;
+  }
+
+  /// Retrieve an iterator over the ring buffer's elements, from front
+  /// to back.
+  #[inline]
+  pub fn iter(&self) -> slice::Iter<'_, T> {
+    self.as_slice().iter()
+  }
+}
+
+impl<T> Drop for MirroredRingBuf<T> {
+  fn drop(&mut self) {
+    let size = self.len * size_of::<T>();
+    // SAFETY: We own both mappings, which are adjacent. `T` is `Copy`
+    //         and so elements don't need to be dropped.
+    let _rc = unsafe { libc::munmap(self.ptr.as_ptr().cast(), 2 * size) };
+  }
+}
+
+impl<T> Debug for MirroredRingBuf<T>
+where
+  T: Copy + Debug,
+{
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.debug_struct("MirroredRingBuf")
+      .field("data", &self.data())
+      .field("front", &self.front)
+      .finish()
+  }
+}
+
+impl<T> Index<usize> for MirroredRingBuf<T>
+where
+  T: Copy,
+{
+  type Output = T;
+
+  #[inline]
+  fn index(&self, idx: usize) -> &Self::Output {
+    &self.as_slice()[idx % self.len]
+  }
+}
+
+impl<T> IndexMut<usize> for MirroredRingBuf<T>
+where
+  T: Copy,
+{
+  #[inline]
+  fn index_mut(&mut self, idx: usize) -> &mut Self::Output {
+    let len = self.len;
+    &mut self.as_mut_slice()[idx % len]
+  }
+}
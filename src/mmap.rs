@@ -0,0 +1,356 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use core::fmt::Debug;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use core::marker::PhantomData;
+use core::mem::align_of;
+use core::mem::size_of;
+use core::ops::Index;
+use core::ops::IndexMut;
+use core::ops::Range;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::ErrorKind;
+use std::io::Read as _;
+use std::path::Path;
+
+use bytemuck::cast_slice;
+use bytemuck::cast_slice_mut;
+use bytemuck::Pod;
+use memmap2::MmapMut;
+
+use crate::RingIter;
+
+
+/// The magic bytes identifying a ring buffer file.
+const MAGIC: [u8; 8] = *b"RBUFRING";
+/// The version of the file format.
+const VERSION: u32 = 1;
+/// The size of the file header, in bytes. Element data start right
+/// after it.
+const HEADER_LEN: usize = 64;
+
+/// The location of the magic bytes in the header.
+const MAGIC_RANGE: Range<usize> = 0..8;
+/// The location of the format version in the header.
+const VERSION_RANGE: Range<usize> = 8..12;
+/// The location of the element size in the header.
+const ELEM_SIZE_RANGE: Range<usize> = 12..16;
+/// The location of the capacity in the header.
+const CAPACITY_RANGE: Range<usize> = 16..24;
+/// The location of the front index in the header.
+const FRONT_RANGE: Range<usize> = 24..32;
+
+
+/// Create an error indicating that the file is not a valid ring buffer
+/// file for the expected configuration.
+#[inline]
+pub(crate) fn invalid_data(msg: &str) -> io::Error {
+  io::Error::new(ErrorKind::InvalidData, msg)
+}
+
+/// Read a little endian `u32` from the provided header range.
+#[inline]
+pub(crate) fn read_u32(header: &[u8], range: Range<usize>) -> u32 {
+  let mut bytes = [0; 4];
+  let () = bytes.copy_from_slice(&header[range]);
+  u32::from_le_bytes(bytes)
+}
+
+/// Read a little endian `u64` from the provided header range.
+#[inline]
+pub(crate) fn read_u64(header: &[u8], range: Range<usize>) -> u64 {
+  let mut bytes = [0; 8];
+  let () = bytes.copy_from_slice(&header[range]);
+  u64::from_le_bytes(bytes)
+}
+
+/// Check whether the header of the provided ring buffer file consists
+/// of zeros only.
+///
+/// That is the case if the process crashed after sizing the file but
+/// before the header got persisted.
+fn is_header_zeroed(mut file: &File) -> io::Result<bool> {
+  let mut header = [0; HEADER_LEN];
+  match file.read_exact(&mut header) {
+    Ok(()) => Ok(header.iter().all(|byte| *byte == 0)),
+    // The file is too short to contain a header. We report that while
+    // validating it.
+    Err(err) if err.kind() == ErrorKind::UnexpectedEof => Ok(false),
+    Err(err) => Err(err),
+  }
+}
+
+
+/// A ring buffer persisted in a memory mapped file.
+///
+/// The file contains a header describing the ring buffer, followed by
+/// its elements. The header stores a magic value, the format version,
+/// the size of an element, the ring buffer's length, and its front
+/// index. As a result, an existing file can be reopened after the
+/// process exited or crashed, and the ring buffer continues where it
+/// left off.
+///
+/// Similar to [`RingBuf`][crate::RingBuf], the ring buffer is always
+/// "full". Elements of a newly created file are all zero.
+///
+/// The file must not be modified by other means while it is open.
+/// Modifications are written back to the file by the operating system
+/// eventually. Use [`MmapRingBuf::flush`] to force them to disk. When
+/// interrupted in the middle of a push, the element pushed may end up
+/// stored as the front element.
+///
+/// # Examples
+/// ```rust
+/// # use rbuf::MmapRingBuf;
+/// # let dir = tempfile::tempdir().unwrap();
+/// # let path = dir.path().join("ring");
+/// let mut buf = MmapRingBuf::<u64>::open(&path, 3).unwrap();
+/// let () = buf.push_back(1);
+/// let () = buf.push_back(2);
+/// drop(buf);
+///
+/// let buf = MmapRingBuf::<u64>::open(&path, 3).unwrap();
+/// assert_eq!(buf.iter().copied().collect::<Vec<_>>(), vec![0, 1, 2]);
+/// ```
+pub struct MmapRingBuf<T> {
+  /// The mapped file, including the header.
+  mmap: MmapMut,
+  /// The number of elements in the ring buffer.
+  len: usize,
+  /// The index of the front element, mirroring the value in the
+  /// header.
+  front: usize,
+  /// Phantom data for our element type.
+  _phantom: PhantomData<T>,
+}
+
+impl<T> MmapRingBuf<T>
+where
+  T: Pod,
+{
+  /// Open the ring buffer file at `path`, creating it with `len`
+  /// zeroed elements if it does not exist or is empty. A file with a
+  /// header consisting of zeros only, as may be left behind by a crash
+  /// during creation, is considered empty as well.
+  ///
+  /// When opening an existing file, its header has to match `len` and
+  /// the element size of `T`. An error of kind
+  /// [`ErrorKind::InvalidData`] is reported otherwise.
+  ///
+  /// # Panics
+  /// This constructor panics if `len` is zero, `T` is zero-sized, or
+  /// `T` has an alignment exceeding 64 bytes.
+  pub fn open<P>(path: P, len: usize) -> io::Result<Self>
+  where
+    P: AsRef<Path>,
+  {
+    assert_ne!(len, 0);
+    assert_ne!(size_of::<T>(), 0, "zero-sized types are not supported");
+    assert!(align_of::<T>() <= HEADER_LEN);
+
+    let elem_size =
+      u32::try_from(size_of::<T>()).map_err(|_err| io::Error::from(ErrorKind::OutOfMemory))?;
+    let file_len = len
+      .checked_mul(size_of::<T>())
+      .and_then(|size| size.checked_add(HEADER_LEN))
+      .and_then(|size| u64::try_from(size).ok())
+      .ok_or_else(|| io::Error::from(ErrorKind::OutOfMemory))?;
+
+    let file = OpenOptions::new()
+      .read(true)
+      .write(true)
+      .create(true)
+      .truncate(false)
+      .open(path)?;
+
+    if file.metadata()?.len() == 0 || is_header_zeroed(&file)? {
+      Self::create(&file, len, elem_size, file_len)
+    } else {
+      Self::reopen(&file, len, file_len)
+    }
+  }
+
+  /// Initialize a new, empty ring buffer file.
+  fn create(file: &File, len: usize, elem_size: u32, file_len: u64) -> io::Result<Self> {
+    let () = file.set_len(file_len)?;
+    // SAFETY: We assume that the file is not modified by anybody else
+    //         while mapped, as documented.
+    let mut mmap = unsafe { MmapMut::map_mut(file)? };
+
+    let header = &mut mmap[..HEADER_LEN];
+    let () = header[VERSION_RANGE].copy_from_slice(&VERSION.to_le_bytes());
+    let () = header[ELEM_SIZE_RANGE].copy_from_slice(&elem_size.to_le_bytes());
+    let () = header[CAPACITY_RANGE].copy_from_slice(&(len as u64).to_le_bytes());
+    let () = header[FRONT_RANGE].copy_from_slice(&0u64.to_le_bytes());
+    // Write the magic last, so that a header left incomplete by a
+    // crash is never mistaken for a valid one.
+    let () = header[MAGIC_RANGE].copy_from_slice(&MAGIC);
+    let () = mmap.flush()?;
+
+    let slf = Self {
+      mmap,
+      len,
+      front: 0,
+      _phantom: PhantomData,
+    };
+    Ok(slf)
+  }
+
+  /// Map an existing ring buffer file, validating its header.
+  fn reopen(file: &File, len: usize, file_len: u64) -> io::Result<Self> {
+    if file.metadata()?.len() != file_len {
+      return Err(invalid_data("ring buffer file has unexpected size"))
+    }
+
+    // SAFETY: We assume that the file is not modified by anybody else
+    //         while mapped, as documented.
+    let mmap = unsafe { MmapMut::map_mut(file)? };
+    let header = &mmap[..HEADER_LEN];
+    if header[MAGIC_RANGE] != MAGIC {
+      return Err(invalid_data("file is not a ring buffer file"))
+    }
+    if read_u32(header, VERSION_RANGE) != VERSION {
+      return Err(invalid_data("ring buffer file has unsupported version"))
+    }
+    if u64::from(read_u32(header, ELEM_SIZE_RANGE)) != size_of::<T>() as u64 {
+      return Err(invalid_data("ring buffer file has mismatching element size"))
+    }
+    if read_u64(header, CAPACITY_RANGE) != len as u64 {
+      return Err(invalid_data("ring buffer file has mismatching length"))
+    }
+
+    let front = read_u64(header, FRONT_RANGE);
+    let front = usize::try_from(front)
+      .ok()
+      .filter(|front| *front < len)
+      .ok_or_else(|| invalid_data("ring buffer file has invalid front index"))?;
+
+    let slf = Self {
+      mmap,
+      len,
+      front,
+      _phantom: PhantomData,
+    };
+    Ok(slf)
+  }
+
+  /// Retrieve the element storage.
+  #[inline]
+  fn data(&self) -> &[T] {
+    cast_slice(&self.mmap[HEADER_LEN..])
+  }
+
+  /// Retrieve the element storage mutably.
+  #[inline]
+  fn data_mut(&mut self) -> &mut [T] {
+    cast_slice_mut(&mut self.mmap[HEADER_LEN..])
+  }
+
+  /// Update the front index, in memory and in the file header.
+  #[inline]
+  fn set_front(&mut self, front: usize) {
+    self.front = front;
+    let () = self.mmap[FRONT_RANGE].copy_from_slice(&(front as u64).to_le_bytes());
+  }
+
+  /// Retrieve the ring buffer's length.
+  #[inline]
+  #[allow(clippy::len_without_is_empty)]
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Retrieve the current front element.
+  #[inline]
+  pub fn front(&self) -> &T {
+    &self.data()[self.front]
+  }
+
+  /// Retrieve the current back element.
+  #[inline]
+  pub fn back(&self) -> &T {
+    let idx = self.front.checked_sub(1).unwrap_or(self.len - 1);
+    &self.data()[idx]
+  }
+
+  /// Push an element into the ring buffer, overwriting the front
+  /// element, which is the oldest one. The element after the current
+  /// front will become the new front.
+  #[inline]
+  pub fn push_back(&mut self, elem: T) {
+    let idx = self.front;
+    self.data_mut()[idx] = elem;
+    let () = self.set_front((idx + 1) % self.len);
+  }
+
+  /// Retrieve the contents of the ring buffer as a pair of slices.
+  ///
+  /// The first slice starts with the front element, the second one ends
+  /// with the back element. Either of them may be empty.
+  #[inline]
+  pub fn as_slices(&self) -> (&[T], &[T]) {
+    let (back, front) = self.data().split_at(self.front);
+    (front, back)
+  }
+
+  /// Retrieve an iterator over the ring buffer's elements, from front
+  /// to back.
+  #[inline]
+  pub fn iter(&self) -> RingIter<'_, T> {
+    let data = self.data();
+    // SAFETY: All `len` elements of our storage are initialized and
+    //         remain borrowed for the lifetime of the iterator.
+    unsafe { RingIter::from_raw_parts(data.as_ptr(), self.len, self.front, self.len) }
+  }
+
+  /// Synchronously write all outstanding modifications back to the
+  /// file.
+  #[inline]
+  pub fn flush(&self) -> io::Result<()> {
+    self.mmap.flush()
+  }
+
+  /// Initiate writing back all outstanding modifications to the file,
+  /// without waiting for the operation to complete.
+  #[inline]
+  pub fn flush_async(&self) -> io::Result<()> {
+    self.mmap.flush_async()
+  }
+}
+
+impl<T> Debug for MmapRingBuf<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.debug_struct("MmapRingBuf")
+      .field("len", &self.len)
+      .field("front", &self.front)
+      .finish()
+  }
+}
+
+impl<T> Index<usize> for MmapRingBuf<T>
+where
+  T: Pod,
+{
+  type Output = T;
+
+  #[inline]
+  fn index(&self, idx: usize) -> &Self::Output {
+    let idx = (self.front + idx) % self.len;
+    &self.data()[idx]
+  }
+}
+
+impl<T> IndexMut<usize> for MmapRingBuf<T>
+where
+  T: Pod,
+{
+  #[inline]
+  fn index_mut(&mut self, idx: usize) -> &mut Self::Output {
+    let idx = (self.front + idx) % self.len;
+    &mut self.data_mut()[idx]
+  }
+}
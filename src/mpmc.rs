@@ -0,0 +1,393 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+//
+// The implementation is derived from `ArrayQueue` of the
+// `crossbeam-queue` crate (https://github.com/crossbeam-rs/crossbeam),
+// which is dual licensed under Apache-2.0 and MIT as well and carries
+// the following notice:
+//
+// Copyright (c) 2019 The Crossbeam Project Developers
+//
+// Permission is hereby granted, free of charge, to any
+// person obtaining a copy of this software and associated
+// documentation files (the "Software"), to deal in the
+// Software without restriction, including without
+// limitation the rights to use, copy, modify, merge,
+// publish, distribute, sublicense, and/or sell copies of
+// the Software, and to permit persons to whom the Software
+// is furnished to do so, subject to the following
+// conditions:
+//
+// The above copyright notice and this permission notice
+// shall be included in all copies or substantial portions
+// of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF
+// ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED
+// TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A
+// PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT
+// SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY
+// CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR
+// IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+use alloc::boxed::Box;
+use core::fmt::Debug;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use core::mem::MaybeUninit;
+use core::ptr;
+
+use crate::sync::atomic::fence;
+use crate::sync::atomic::AtomicUsize;
+use crate::sync::atomic::Ordering;
+use crate::sync::spin_loop;
+use crate::sync::CachePadded;
+use crate::sync::UnsafeCell;
+
+
+/// A slot in an [`MpmcRing`].
+struct Slot<T> {
+  /// The stamp of the slot.
+  ///
+  /// If the stamp equals the tail, the slot is empty and the next push
+  /// may write to it. If it equals the head plus one, it contains an
+  /// element that the next pop may take.
+  stamp: AtomicUsize,
+  /// The slot's element, if any.
+  value: UnsafeCell<MaybeUninit<T>>,
+}
+
+
+/// A lock-free multi-producer/multi-consumer ring buffer with a fixed
+/// capacity.
+///
+/// An `MpmcRing` can be shared between an arbitrary number of threads
+/// (e.g., by wrapping it in an `Arc`), all of which may push and pop
+/// elements concurrently.
+///
+/// The implementation is adapted from `crossbeam-queue`'s `ArrayQueue`
+/// and follows the design by Dmitry Vyukov, in which each slot carries
+/// a sequence number (stamp) that tells pushing and popping threads
+/// whether the slot is ready for them. The head and tail counters are
+/// split into an index and a "lap" component, where the lap is
+/// incremented every time a counter wraps around.
+///
+/// # Examples
+/// ```rust
+/// # use rbuf::MpmcRing;
+/// let ring = MpmcRing::new(2);
+/// assert_eq!(ring.try_push(1), Ok(()));
+/// assert_eq!(ring.try_push(2), Ok(()));
+/// assert_eq!(ring.try_push(3), Err(3));
+/// assert_eq!(ring.force_push(3), Some(1));
+/// assert_eq!(ring.try_pop(), Some(2));
+/// assert_eq!(ring.try_pop(), Some(3));
+/// assert_eq!(ring.try_pop(), None);
+/// ```
+pub struct MpmcRing<T> {
+  /// The counter of the next slot to pop from, consisting of an index
+  /// and a lap.
+  head: CachePadded<AtomicUsize>,
+  /// The counter of the next slot to push to, consisting of an index
+  /// and a lap.
+  tail: CachePadded<AtomicUsize>,
+  /// The value to add to a counter to advance it by one lap. It is a
+  /// power of two greater than the capacity, so that the lower bits
+  /// of a counter represent the index.
+  one_lap: usize,
+  /// Our actual data.
+  data: Box<[Slot<T>]>,
+}
+
+// SAFETY: Access to each slot is coordinated through its stamp, which
+//         hands out exclusive ownership of the slot to a single thread
+//         at a time. Elements are only ever moved between threads, never
+//         shared.
+unsafe impl<T> Send for MpmcRing<T> where T: Send {}
+// SAFETY: See above.
+unsafe impl<T> Sync for MpmcRing<T> where T: Send {}
+
+impl<T> MpmcRing<T> {
+  /// Create a new `MpmcRing` with capacity for `cap` elements.
+  ///
+  /// # Panics
+  /// This constructor panics if `cap` is zero.
+  pub fn new(cap: usize) -> Self {
+    assert_ne!(cap, 0);
+
+    let data = (0..cap)
+      .map(|idx| Slot {
+        stamp: AtomicUsize::new(idx),
+        value: UnsafeCell::new(MaybeUninit::uninit()),
+      })
+      .collect();
+
+    Self {
+      head: CachePadded(AtomicUsize::new(0)),
+      tail: CachePadded(AtomicUsize::new(0)),
+      one_lap: (cap + 1).next_power_of_two(),
+      data,
+    }
+  }
+
+  /// Retrieve the ring buffer's capacity.
+  #[inline]
+  pub fn capacity(&self) -> usize {
+    self.data.len()
+  }
+
+  /// Retrieve the index component of a counter.
+  #[inline]
+  fn index(&self, counter: usize) -> usize {
+    counter & (self.one_lap - 1)
+  }
+
+  /// Advance a counter by one slot, moving on to the next lap if
+  /// necessary.
+  #[inline]
+  fn advance(&self, counter: usize) -> usize {
+    if self.index(counter) + 1 < self.capacity() {
+      counter + 1
+    } else {
+      (counter & !(self.one_lap - 1)).wrapping_add(self.one_lap)
+    }
+  }
+
+  /// Push an element, calling `full` when the ring buffer appears to
+  /// be full.
+  ///
+  /// `full` receives the element, the current tail, the advanced tail,
+  /// and the slot at the tail. It returns `Ok` with the element to
+  /// retry pushing it and `Err` with an element to bail out with.
+  fn push_or_else<F>(&self, mut elem: T, full: F) -> Result<(), T>
+  where
+    F: Fn(T, usize, usize, &Slot<T>) -> Result<T, T>,
+  {
+    let mut tail = self.tail.load(Ordering::Relaxed);
+
+    loop {
+      let new_tail = self.advance(tail);
+      let slot = &self.data[self.index(tail)];
+      let stamp = slot.stamp.load(Ordering::Acquire);
+
+      if tail == stamp {
+        // The slot is empty. Try claiming it.
+        let result = self.tail.compare_exchange_weak(
+          tail,
+          new_tail,
+          Ordering::SeqCst,
+          Ordering::Relaxed,
+        );
+        match result {
+          Ok(_) => {
+            // SAFETY: We claimed the empty slot, giving us exclusive
+            //         access to it.
+            let () = slot.value.with_mut(|ptr| unsafe {
+              let _elem = (*ptr).write(elem);
+            });
+            let () = slot.stamp.store(tail + 1, Ordering::Release);
+            break Ok(())
+          },
+          Err(current) => {
+            tail = current;
+            let () = spin_loop();
+          },
+        }
+      } else if stamp.wrapping_add(self.one_lap) == tail + 1 {
+        // The slot still holds the element from the previous lap, so
+        // the ring buffer may be full.
+        let () = fence(Ordering::SeqCst);
+        elem = full(elem, tail, new_tail, slot)?;
+        let () = spin_loop();
+        tail = self.tail.load(Ordering::Relaxed);
+      } else {
+        // Another thread claimed the slot but has not finished pushing
+        // to it yet.
+        let () = spin_loop();
+        tail = self.tail.load(Ordering::Relaxed);
+      }
+    }
+  }
+
+  /// Attempt to push an element into the ring buffer.
+  ///
+  /// If the ring buffer is full, the element is handed back as `Err`.
+  pub fn try_push(&self, elem: T) -> Result<(), T> {
+    self.push_or_else(elem, |elem, tail, _, _| {
+      let head = self.head.load(Ordering::Relaxed);
+      if head.wrapping_add(self.one_lap) == tail {
+        Err(elem)
+      } else {
+        Ok(elem)
+      }
+    })
+  }
+
+  /// Push an element into the ring buffer, evicting the oldest element
+  /// if it is full.
+  ///
+  /// The evicted element, if any, is returned.
+  pub fn force_push(&self, elem: T) -> Option<T> {
+    self
+      .push_or_else(elem, |elem, tail, new_tail, slot| {
+        let head = tail.wrapping_sub(self.one_lap);
+        let new_head = new_tail.wrapping_sub(self.one_lap);
+
+        // Try claiming the oldest element by moving the head.
+        let result = self.head.compare_exchange_weak(
+          head,
+          new_head,
+          Ordering::SeqCst,
+          Ordering::Relaxed,
+        );
+        if result.is_ok() {
+          let () = self.tail.store(new_tail, Ordering::SeqCst);
+          // SAFETY: We claimed both the oldest element and the slot it
+          //         lives in, giving us exclusive access to it.
+          let evicted = slot.value.with_mut(|ptr| unsafe {
+            ptr::replace(ptr, MaybeUninit::new(elem)).assume_init()
+          });
+          let () = slot.stamp.store(tail + 1, Ordering::Release);
+          Err(evicted)
+        } else {
+          Ok(elem)
+        }
+      })
+      .err()
+  }
+
+  /// Attempt to pop the oldest element from the ring buffer.
+  ///
+  /// `None` is returned if the ring buffer is empty.
+  pub fn try_pop(&self) -> Option<T> {
+    let mut head = self.head.load(Ordering::Relaxed);
+
+    loop {
+      let slot = &self.data[self.index(head)];
+      let stamp = slot.stamp.load(Ordering::Acquire);
+
+      if head + 1 == stamp {
+        // The slot holds an element. Try claiming it.
+        let result = self.head.compare_exchange_weak(
+          head,
+          self.advance(head),
+          Ordering::SeqCst,
+          Ordering::Relaxed,
+        );
+        match result {
+          Ok(_) => {
+            // SAFETY: We claimed the full slot, giving us exclusive
+            //         access to it.
+            let elem = slot
+              .value
+              .with(|ptr| unsafe { ptr.read().assume_init() });
+            let () = slot
+              .stamp
+              .store(head.wrapping_add(self.one_lap), Ordering::Release);
+            break Some(elem)
+          },
+          Err(current) => {
+            head = current;
+            let () = spin_loop();
+          },
+        }
+      } else if stamp == head {
+        // The slot is empty, so the ring buffer may be empty.
+        let () = fence(Ordering::SeqCst);
+        let tail = self.tail.load(Ordering::Relaxed);
+        if tail == head {
+          break None
+        }
+
+        let () = spin_loop();
+        head = self.head.load(Ordering::Relaxed);
+      } else {
+        // Another thread claimed the slot but has not finished popping
+        // from it yet.
+        let () = spin_loop();
+        head = self.head.load(Ordering::Relaxed);
+      }
+    }
+  }
+
+  /// Retrieve the number of elements in the ring buffer.
+  ///
+  /// Because other threads may operate on the ring buffer concurrently,
+  /// the value is only a snapshot.
+  pub fn len(&self) -> usize {
+    loop {
+      let tail = self.tail.load(Ordering::SeqCst);
+      let head = self.head.load(Ordering::SeqCst);
+
+      // Make sure we have a consistent view of both counters.
+      if self.tail.load(Ordering::SeqCst) == tail {
+        let head_idx = self.index(head);
+        let tail_idx = self.index(tail);
+
+        break if head_idx < tail_idx {
+          tail_idx - head_idx
+        } else if head_idx > tail_idx {
+          self.capacity() - head_idx + tail_idx
+        } else if tail == head {
+          0
+        } else {
+          self.capacity()
+        }
+      }
+    }
+  }
+
+  /// Check whether the ring buffer is empty.
+  ///
+  /// Because other threads may operate on the ring buffer concurrently,
+  /// the value is only a snapshot.
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    let head = self.head.load(Ordering::SeqCst);
+    let tail = self.tail.load(Ordering::SeqCst);
+    tail == head
+  }
+
+  /// Check whether the ring buffer is full.
+  ///
+  /// Because other threads may operate on the ring buffer concurrently,
+  /// the value is only a snapshot.
+  #[inline]
+  pub fn is_full(&self) -> bool {
+    let tail = self.tail.load(Ordering::SeqCst);
+    let head = self.head.load(Ordering::SeqCst);
+    head.wrapping_add(self.one_lap) == tail
+  }
+}
+
+impl<T> Debug for MpmcRing<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.debug_struct("MpmcRing")
+      .field("capacity", &self.capacity())
+      .field("len", &self.len())
+      .finish()
+  }
+}
+
+impl<T> Drop for MpmcRing<T> {
+  fn drop(&mut self) {
+    let head = self.head.load(Ordering::Relaxed);
+    let mut idx = self.index(head);
+
+    for _ in 0..self.len() {
+      let slot = &self.data[idx];
+      // SAFETY: We have exclusive access to all slots and the `len`
+      //         ones starting at the head are initialized.
+      let _elem = slot
+        .value
+        .with(|ptr| unsafe { ptr.read().assume_init() });
+
+      idx += 1;
+      if idx == self.capacity() {
+        idx = 0;
+      }
+    }
+  }
+}
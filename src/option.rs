@@ -0,0 +1,164 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use core::iter::Enumerate;
+use core::iter::FusedIterator;
+
+use crate::RingBuf;
+use crate::RingIter;
+use crate::RingIterMut;
+use crate::Storage;
+
+
+/// An iterator over the `Some` elements of a `RingBuf<Option<T>>`,
+/// along with their logical indexes.
+///
+/// Iteration happens front-to-back, unless reversed.
+#[derive(Clone, Debug)]
+pub struct SomeIter<'b, T> {
+  /// The iterator over all elements, including `None` ones.
+  iter: Enumerate<RingIter<'b, Option<T>>>,
+}
+
+impl<'b, T> Iterator for SomeIter<'b, T> {
+  type Item = (usize, &'b T);
+
+  #[inline]
+  fn next(&mut self) -> Option<Self::Item> {
+    self
+      .iter
+      .by_ref()
+      .find_map(|(idx, elem)| elem.as_ref().map(|elem| (idx, elem)))
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let (_, upper) = self.iter.size_hint();
+    (0, upper)
+  }
+}
+
+impl<T> DoubleEndedIterator for SomeIter<'_, T> {
+  #[inline]
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self
+      .iter
+      .by_ref()
+      .rev()
+      .find_map(|(idx, elem)| elem.as_ref().map(|elem| (idx, elem)))
+  }
+}
+
+impl<T> FusedIterator for SomeIter<'_, T> {}
+
+
+/// A draining iterator taking all `Some` elements out of a
+/// `RingBuf<Option<T>>`, leaving `None` in their place.
+///
+/// Iteration happens front-to-back, unless reversed. Elements not
+/// consumed by the time the iterator is dropped are dropped along with
+/// it.
+#[derive(Debug)]
+pub struct TakeAll<'b, T> {
+  /// The iterator over all elements, including `None` ones.
+  iter: RingIterMut<'b, Option<T>>,
+}
+
+impl<T> Iterator for TakeAll<'_, T> {
+  type Item = T;
+
+  #[inline]
+  fn next(&mut self) -> Option<Self::Item> {
+    self.iter.by_ref().find_map(Option::take)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    let (_, upper) = self.iter.size_hint();
+    (0, upper)
+  }
+}
+
+impl<T> DoubleEndedIterator for TakeAll<'_, T> {
+  #[inline]
+  fn next_back(&mut self) -> Option<Self::Item> {
+    self.iter.by_ref().rev().find_map(Option::take)
+  }
+}
+
+impl<T> FusedIterator for TakeAll<'_, T> {}
+
+impl<T> Drop for TakeAll<'_, T> {
+  fn drop(&mut self) {
+    let () = self.for_each(drop);
+  }
+}
+
+
+/// Helpers for working with ring buffers that represent gaps by means
+/// of `None` elements.
+impl<T, S> RingBuf<Option<T>, S>
+where
+  S: Storage<Option<T>>,
+{
+  /// Count the number of `Some` elements in the ring buffer.
+  #[inline]
+  pub fn count_some(&self) -> usize {
+    self.iter().filter(|elem| elem.is_some()).count()
+  }
+
+  /// Retrieve an iterator over all `Some` elements of the ring buffer,
+  /// along with their logical indexes.
+  ///
+  /// The iterator traverses the ring buffer in front-to-back manner.
+  #[inline]
+  pub fn iter_some(&self) -> SomeIter<'_, T> {
+    SomeIter {
+      iter: self.iter().enumerate(),
+    }
+  }
+
+  /// Retrieve the `Some` element closest to the front of the ring
+  /// buffer, along with its logical index.
+  #[inline]
+  pub fn first_some(&self) -> Option<(usize, &T)> {
+    self.iter_some().next()
+  }
+
+  /// Retrieve the `Some` element closest to the back of the ring
+  /// buffer, along with its logical index.
+  #[inline]
+  pub fn last_some(&self) -> Option<(usize, &T)> {
+    self.iter_some().next_back()
+  }
+
+  /// Move all `Some` elements to the back of the ring buffer, while
+  /// preserving their relative order.
+  ///
+  /// After this operation all `None` elements are located at the front.
+  pub fn compact(&mut self) {
+    let mut dst = self.len();
+    for src in (0..self.len()).rev() {
+      if self[src].is_some() {
+        dst -= 1;
+        if dst != src {
+          self[dst] = self[src].take();
+        }
+      }
+    }
+  }
+
+  /// Retrieve a draining iterator that takes all `Some` elements out of
+  /// the ring buffer, leaving `None` in their place.
+  ///
+  /// The iterator traverses the ring buffer in front-to-back manner.
+  ///
+  /// # Panics
+  /// This method panics when `Option<T>` is a zero sized type.
+  #[inline]
+  pub fn take_all(&mut self) -> TakeAll<'_, T> {
+    TakeAll {
+      iter: self.iter_mut(),
+    }
+  }
+}
@@ -0,0 +1,308 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+//! An asynchronous in-memory byte pipe backed by a fixed size ring
+//! buffer.
+//!
+//! A unidirectional pipe is created by means of [`pipe`], which returns
+//! a [`PipeWriter`] implementing [`AsyncWrite`] and a [`PipeReader`]
+//! implementing [`AsyncRead`]. Writers wait while the pipe is full,
+//! readers wait while it is empty. A bidirectional pipe, consisting of
+//! two [`DuplexStream`]s, can be created by means of [`duplex`].
+//!
+//! Once the writing half got dropped or shut down, the reading half
+//! reads the remaining data followed by end-of-file. Once the reading
+//! half got dropped or the writing half shut down, writes fail with
+//! [`ErrorKind::BrokenPipe`].
+//!
+//! # Examples
+//! ```rust
+//! # use futures::executor::block_on;
+//! use tokio::io::AsyncReadExt as _;
+//! use tokio::io::AsyncWriteExt as _;
+//! use rbuf::pipe::pipe;
+//!
+//! let (mut writer, mut reader) = pipe(16);
+//! let () = block_on(async {
+//!   let () = writer.write_all(b"hello").await.unwrap();
+//!   drop(writer);
+//!
+//!   let mut data = Vec::new();
+//!   let _n = reader.read_to_end(&mut data).await.unwrap();
+//!   assert_eq!(data, b"hello");
+//! });
+//! ```
+
+use core::pin::Pin;
+use core::task::Context;
+use core::task::Poll;
+use core::task::Waker;
+use std::io;
+use std::io::ErrorKind;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
+use tokio::io::ReadBuf;
+
+use crate::ByteRing;
+
+
+/// Remember the waker of the provided context in `slot`.
+///
+/// The stored waker is only replaced if it would not wake the same task
+/// anyway, to avoid needless clones.
+#[inline]
+fn register(slot: &mut Option<Waker>, cx: &Context<'_>) {
+  match slot {
+    Some(waker) if waker.will_wake(cx.waker()) => (),
+    _ => *slot = Some(cx.waker().clone()),
+  }
+}
+
+/// Release the lock on `state` and wake the task whose waker is stored
+/// in the slot selected by `slot`, if any.
+///
+/// Waking happens only after the lock got released, so that the woken
+/// task does not immediately contend for it.
+#[inline]
+fn wake(mut state: MutexGuard<'_, State>, slot: fn(&mut State) -> &mut Option<Waker>) {
+  let waker = slot(&mut state).take();
+  drop(state);
+
+  if let Some(waker) = waker {
+    let () = waker.wake();
+  }
+}
+
+
+/// The state shared between the writing and the reading half of a
+/// pipe.
+#[derive(Debug)]
+struct State {
+  /// The buffered data.
+  ring: ByteRing,
+  /// The waker of the reader's task, if it is waiting for data.
+  reader_waker: Option<Waker>,
+  /// The waker of the writer's task, if it is waiting for space.
+  writer_waker: Option<Waker>,
+  /// Whether the writing half has been dropped or shut down.
+  writer_closed: bool,
+  /// Whether the reading half has been dropped.
+  reader_closed: bool,
+}
+
+/// The lock protected state shared between the writing and the reading
+/// half of a pipe.
+#[derive(Debug)]
+struct Shared(Mutex<State>);
+
+impl Shared {
+  /// Lock the shared state.
+  ///
+  /// None of our operations can leave the state inconsistent, so we
+  /// ignore lock poisoning.
+  #[inline]
+  fn lock(&self) -> MutexGuard<'_, State> {
+    self.0.lock().unwrap_or_else(PoisonError::into_inner)
+  }
+}
+
+
+/// Create a new unidirectional pipe buffering up to `capacity` bytes.
+///
+/// # Panics
+/// This function panics if `capacity` is zero.
+pub fn pipe(capacity: usize) -> (PipeWriter, PipeReader) {
+  let shared = Arc::new(Shared(Mutex::new(State {
+    ring: ByteRing::new(capacity),
+    reader_waker: None,
+    writer_waker: None,
+    writer_closed: false,
+    reader_closed: false,
+  })));
+
+  let writer = PipeWriter {
+    shared: Arc::clone(&shared),
+  };
+  let reader = PipeReader { shared };
+  (writer, reader)
+}
+
+/// Create a new bidirectional pipe, buffering up to `capacity` bytes in
+/// each direction.
+///
+/// Data written to one of the returned streams can be read from the
+/// other one.
+///
+/// # Panics
+/// This function panics if `capacity` is zero.
+pub fn duplex(capacity: usize) -> (DuplexStream, DuplexStream) {
+  let (writer1, reader1) = pipe(capacity);
+  let (writer2, reader2) = pipe(capacity);
+
+  let stream1 = DuplexStream {
+    writer: writer1,
+    reader: reader2,
+  };
+  let stream2 = DuplexStream {
+    writer: writer2,
+    reader: reader1,
+  };
+  (stream1, stream2)
+}
+
+
+/// The writing half of a pipe.
+#[derive(Debug)]
+pub struct PipeWriter {
+  /// The state shared with the reader.
+  shared: Arc<Shared>,
+}
+
+impl PipeWriter {
+  /// Mark the writing half as closed, waking the reader so that it can
+  /// observe the end of the data.
+  fn close(&self) {
+    let mut state = self.shared.lock();
+    state.writer_closed = true;
+    let () = wake(state, |state| &mut state.reader_waker);
+  }
+}
+
+impl Drop for PipeWriter {
+  fn drop(&mut self) {
+    self.close()
+  }
+}
+
+impl AsyncWrite for PipeWriter {
+  fn poll_write(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &[u8],
+  ) -> Poll<io::Result<usize>> {
+    let mut state = self.shared.lock();
+    if state.reader_closed || state.writer_closed {
+      return Poll::Ready(Err(io::Error::from(ErrorKind::BrokenPipe)))
+    }
+
+    if buf.is_empty() {
+      return Poll::Ready(Ok(0))
+    }
+
+    if state.ring.is_full() {
+      let () = register(&mut state.writer_waker, cx);
+      return Poll::Pending
+    }
+
+    let n = state.ring.push_slice(buf);
+    let () = wake(state, |state| &mut state.reader_waker);
+    Poll::Ready(Ok(n))
+  }
+
+  fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+    Poll::Ready(Ok(()))
+  }
+
+  fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+    let () = self.close();
+    Poll::Ready(Ok(()))
+  }
+}
+
+
+/// The reading half of a pipe.
+#[derive(Debug)]
+pub struct PipeReader {
+  /// The state shared with the writer.
+  shared: Arc<Shared>,
+}
+
+impl Drop for PipeReader {
+  fn drop(&mut self) {
+    let mut state = self.shared.lock();
+    state.reader_closed = true;
+    let () = wake(state, |state| &mut state.writer_waker);
+  }
+}
+
+impl AsyncRead for PipeReader {
+  fn poll_read(
+    self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &mut ReadBuf<'_>,
+  ) -> Poll<io::Result<()>> {
+    let mut state = self.shared.lock();
+    if buf.remaining() == 0 {
+      return Poll::Ready(Ok(()))
+    }
+
+    if state.ring.is_empty() {
+      if state.writer_closed {
+        return Poll::Ready(Ok(()))
+      }
+
+      let () = register(&mut state.reader_waker, cx);
+      return Poll::Pending
+    }
+
+    let (first, second) = state.ring.as_slices();
+    let n1 = first.len().min(buf.remaining());
+    let () = buf.put_slice(&first[..n1]);
+    let n2 = second.len().min(buf.remaining());
+    let () = buf.put_slice(&second[..n2]);
+
+    let () = state.ring.consume(n1 + n2);
+    let () = wake(state, |state| &mut state.writer_waker);
+    Poll::Ready(Ok(()))
+  }
+}
+
+
+/// One end of a bidirectional pipe.
+///
+/// Data written to a `DuplexStream` can be read from its peer and vice
+/// versa.
+#[derive(Debug)]
+pub struct DuplexStream {
+  /// The writing half, connected to the peer's reading half.
+  writer: PipeWriter,
+  /// The reading half, connected to the peer's writing half.
+  reader: PipeReader,
+}
+
+impl AsyncWrite for DuplexStream {
+  #[inline]
+  fn poll_write(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &[u8],
+  ) -> Poll<io::Result<usize>> {
+    Pin::new(&mut self.writer).poll_write(cx, buf)
+  }
+
+  #[inline]
+  fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+    Pin::new(&mut self.writer).poll_flush(cx)
+  }
+
+  #[inline]
+  fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+    Pin::new(&mut self.writer).poll_shutdown(cx)
+  }
+}
+
+impl AsyncRead for DuplexStream {
+  #[inline]
+  fn poll_read(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+    buf: &mut ReadBuf<'_>,
+  ) -> Poll<io::Result<()>> {
+    Pin::new(&mut self.reader).poll_read(cx, buf)
+  }
+}
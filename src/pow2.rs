@@ -0,0 +1,178 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ops::Deref;
+use core::ops::DerefMut;
+
+use crate::RingBuf;
+use crate::Storage;
+
+
+/// Ring buffer storage with a power-of-two length.
+///
+/// A [`RingBuf`] using `Pow2` storage tracks its front element by means
+/// of a free-running counter, which is mapped onto a storage index by
+/// bit masking instead of the more costly modulo operation.
+///
+/// ```rust
+/// # use rbuf::Pow2;
+/// # use rbuf::RingBuf;
+/// let mut buf = RingBuf::from_storage(Pow2::new([1, 2, 3, 4]));
+/// let () = buf.push_back(5);
+/// assert_eq!(*buf.front(), 2);
+/// assert_eq!(buf[3], 5);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pow2<S>(S);
+
+impl<S> Pow2<S> {
+  /// Wrap the provided storage.
+  ///
+  /// # Panics
+  /// This constructor panics if the storage's length is not a power of
+  /// two.
+  #[inline]
+  pub fn new<T>(storage: S) -> Self
+  where
+    S: AsRef<[T]>,
+  {
+    let len = storage.as_ref().len();
+    assert!(
+      len.is_power_of_two(),
+      "ring buffer length {len} is not a power of two"
+    );
+
+    Self(storage)
+  }
+
+  /// Retrieve the wrapped storage.
+  #[inline]
+  pub fn into_inner(self) -> S {
+    self.0
+  }
+}
+
+impl<T, S> AsRef<[T]> for Pow2<S>
+where
+  S: AsRef<[T]>,
+{
+  #[inline]
+  fn as_ref(&self) -> &[T] {
+    self.0.as_ref()
+  }
+}
+
+impl<T, S> AsMut<[T]> for Pow2<S>
+where
+  S: AsMut<[T]>,
+{
+  #[inline]
+  fn as_mut(&mut self) -> &mut [T] {
+    self.0.as_mut()
+  }
+}
+
+// SAFETY: The wrapped storage's length is constant and a power of two,
+//         so masking always results in an index less than it.
+unsafe impl<T, S> Storage<T> for Pow2<S>
+where
+  S: Storage<T>,
+{
+  #[inline]
+  fn index(pos: usize, len: usize) -> usize {
+    pos & (len - 1)
+  }
+
+  // Positions are free-running counters. Because `len` is a power of
+  // two and, hence, divides `usize::MAX + 1`, they can wrap around at
+  // the end of the integer range without affecting the index they map
+  // to.
+  #[inline]
+  fn advance(pos: usize, n: usize, _len: usize) -> usize {
+    pos.wrapping_add(n)
+  }
+
+  #[inline]
+  fn retreat(pos: usize, n: usize, _len: usize) -> usize {
+    pos.wrapping_sub(n)
+  }
+}
+
+
+/// A ring buffer with a power-of-two length.
+///
+/// A `Pow2RingBuf` is a thin wrapper around a [`RingBuf`] using
+/// [`Pow2`] storage, providing the same set of constructors as a
+/// `RingBuf` with default storage. All other functionality is
+/// accessible through its [`Deref`] implementation.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pow2RingBuf<T>(RingBuf<T, Pow2<Box<[T]>>>);
+
+impl<T> Pow2RingBuf<T>
+where
+  T: Default,
+{
+  /// Create a new `Pow2RingBuf` of a fixed length as provided.
+  ///
+  /// # Panics
+  /// This constructor panics if `len` is not a power of two.
+  pub fn new(len: usize) -> Self {
+    let mut vec = Vec::with_capacity(len);
+    vec.resize_with(len, Default::default);
+
+    Self::from_vec(vec)
+  }
+}
+
+impl<T> Pow2RingBuf<T> {
+  /// Create a new `Pow2RingBuf` with data from a `Vec`.
+  ///
+  /// Note that the vector's first element is considered the front.
+  ///
+  /// # Panics
+  /// This constructor panics if the provided vector's length is not a
+  /// power of two.
+  #[inline]
+  pub fn from_vec(vec: Vec<T>) -> Self {
+    Self::from(vec.into_boxed_slice())
+  }
+
+  /// Convert the `Pow2RingBuf` into a boxed slice of its contents.
+  ///
+  /// The slice's first element will represents the (former) ring
+  /// buffer's front its last element the buffer's back.
+  #[inline]
+  pub fn into_boxed_slice(self) -> Box<[T]> {
+    self.0.into_storage().into_inner()
+  }
+}
+
+impl<T> Deref for Pow2RingBuf<T> {
+  type Target = RingBuf<T, Pow2<Box<[T]>>>;
+
+  #[inline]
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
+
+impl<T> DerefMut for Pow2RingBuf<T> {
+  #[inline]
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    &mut self.0
+  }
+}
+
+/// Create a `Pow2RingBuf` from a boxed slice.
+///
+/// # Panics
+/// This conversion panics if the provided slice's length is not a power
+/// of two.
+impl<T> From<Box<[T]>> for Pow2RingBuf<T> {
+  #[inline]
+  fn from(other: Box<[T]>) -> Self {
+    Self(RingBuf::from_storage(Pow2::new(other)))
+  }
+}
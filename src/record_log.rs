@@ -0,0 +1,605 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use core::fmt::Debug;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use core::iter::FusedIterator;
+use core::ops::Range;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use crc32fast::Hasher;
+use memmap2::MmapMut;
+
+use crate::mmap::invalid_data;
+use crate::mmap::read_u32;
+use crate::mmap::read_u64;
+
+
+/// The magic bytes identifying a record log file.
+const MAGIC: [u8; 8] = *b"RBUFRLOG";
+/// The version of the file format.
+const VERSION: u32 = 1;
+/// The size of the file header, in bytes. Record data start right
+/// after it.
+const HEADER_LEN: usize = 64;
+
+/// The location of the magic bytes in the header.
+const MAGIC_RANGE: Range<usize> = 0..8;
+/// The location of the format version in the header.
+const VERSION_RANGE: Range<usize> = 8..12;
+/// The location of the capacity in the header.
+const CAPACITY_RANGE: Range<usize> = 16..24;
+
+/// The size of a record's header, in bytes.
+const RECORD_HEADER_LEN: usize = 16;
+/// The location of the payload length in a record's header.
+const LEN_RANGE: Range<usize> = 0..4;
+/// The location of the checksum in a record's header.
+const CRC_RANGE: Range<usize> = 4..8;
+/// The location of the sequence number in a record's header.
+const SEQ_RANGE: Range<usize> = 8..16;
+/// The length value marking the remainder of the data area as unused,
+/// with the next record being located at its start.
+const PAD: u32 = u32::MAX;
+
+
+/// The interpretation of the data at a given offset.
+#[derive(Clone, Copy, Debug)]
+enum Slot {
+  /// The offset contains a valid record.
+  Record {
+    /// The record's sequence number.
+    seq: u64,
+    /// The offset just past the record.
+    end: usize,
+  },
+  /// The next record is located at the start of the data area.
+  Wrap,
+  /// The offset does not contain a valid record.
+  Invalid,
+}
+
+/// Calculate the checksum of a record.
+fn checksum(len: &[u8], seq: &[u8], payload: &[u8]) -> u32 {
+  let mut hasher = Hasher::new();
+  let () = hasher.update(len);
+  let () = hasher.update(seq);
+  let () = hasher.update(payload);
+  hasher.finalize()
+}
+
+/// Check whether the next record after offset `off` of the data area
+/// `data` is located at the start of the data area.
+#[inline]
+fn wraps(data: &[u8], off: usize) -> bool {
+  data.len() - off < RECORD_HEADER_LEN || read_u32(&data[off..], LEN_RANGE) == PAD
+}
+
+/// Read the record at offset `off` of the data area `data`, which is
+/// known to be valid.
+#[inline]
+fn record_at(data: &[u8], off: usize) -> Record<'_> {
+  let header = &data[off..off + RECORD_HEADER_LEN];
+  let len = read_u32(header, LEN_RANGE) as usize;
+  let start = off + RECORD_HEADER_LEN;
+  Record {
+    seq: read_u64(header, SEQ_RANGE),
+    data: &data[start..start + len],
+  }
+}
+
+/// Interpret the data at offset `off` of the data area `data`.
+fn slot(data: &[u8], off: usize) -> Slot {
+  if wraps(data, off) {
+    return Slot::Wrap
+  }
+
+  let header = &data[off..off + RECORD_HEADER_LEN];
+  let len = read_u32(header, LEN_RANGE);
+
+  let start = off + RECORD_HEADER_LEN;
+  let len = len as usize;
+  if len > data.len() - start {
+    return Slot::Invalid
+  }
+
+  let payload = &data[start..start + len];
+  let crc = checksum(&header[LEN_RANGE], &header[SEQ_RANGE], payload);
+  if crc != read_u32(header, CRC_RANGE) {
+    return Slot::Invalid
+  }
+
+  Slot::Record {
+    seq: read_u64(header, SEQ_RANGE),
+    end: start + len,
+  }
+}
+
+
+/// A chain of consecutive records, as found during recovery.
+#[derive(Clone, Copy, Debug)]
+struct Chain {
+  /// The offset of the first record.
+  start: usize,
+  /// The offset of the last record.
+  last: usize,
+  /// The offset just past the last record.
+  end: usize,
+  /// The sequence number of the first record.
+  first_seq: u64,
+  /// The number of records.
+  count: usize,
+  /// Whether the chain is terminated by a wrap around.
+  wraps: bool,
+}
+
+impl Chain {
+  /// Follow the records starting at offset `start`, for as long as
+  /// their sequence numbers are consecutive.
+  fn follow(data: &[u8], start: usize) -> Self {
+    let mut chain = Self {
+      start,
+      last: start,
+      end: start,
+      first_seq: 0,
+      count: 0,
+      wraps: false,
+    };
+
+    loop {
+      match slot(data, chain.end) {
+        Slot::Record { seq, end } if chain.count == 0 || seq == chain.next_seq() => {
+          if chain.count == 0 {
+            chain.first_seq = seq;
+          }
+          chain.count += 1;
+          chain.last = chain.end;
+          chain.end = end;
+        },
+        Slot::Wrap => {
+          chain.wraps = true;
+          break chain
+        },
+        Slot::Record { .. } | Slot::Invalid => break chain,
+      }
+    }
+  }
+
+  /// Retrieve the sequence number following the chain's last record.
+  #[inline]
+  fn next_seq(&self) -> u64 {
+    self.first_seq + self.count as u64
+  }
+}
+
+
+/// A record stored in a [`RecordLog`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Record<'log> {
+  /// The record's sequence number.
+  pub seq: u64,
+  /// The record's payload.
+  pub data: &'log [u8],
+}
+
+
+/// An iterator over the records of a [`RecordLog`], from oldest to
+/// newest.
+#[derive(Clone)]
+pub struct RecordIter<'log> {
+  /// The log's data area.
+  data: &'log [u8],
+  /// The offset of the next record to yield.
+  off: usize,
+  /// The number of records left to yield.
+  count: usize,
+}
+
+impl<'log> Iterator for RecordIter<'log> {
+  type Item = Record<'log>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.count == 0 {
+      return None
+    }
+
+    if wraps(self.data, self.off) {
+      self.off = 0;
+    }
+
+    let record = record_at(self.data, self.off);
+    self.off += RECORD_HEADER_LEN + record.data.len();
+    self.count -= 1;
+    Some(record)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.count, Some(self.count))
+  }
+}
+
+impl ExactSizeIterator for RecordIter<'_> {}
+
+impl FusedIterator for RecordIter<'_> {}
+
+impl Debug for RecordIter<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.debug_struct("RecordIter")
+      .field("off", &self.off)
+      .field("count", &self.count)
+      .finish()
+  }
+}
+
+
+/// A ring buffer of variable length records, persisted in a memory
+/// mapped file.
+///
+/// Each record is stored along with its length, a sequence number, and
+/// a CRC32 checksum. Once the log is full, pushing a record overwrites
+/// the oldest ones, just like [`RingBuf::push_back`][crate::RingBuf::push_back]
+/// does for fixed size elements. Records are never split across the
+/// end of the file.
+///
+/// When reopening an existing file, the log is recovered from the
+/// records themselves: starting at the newest records, all valid and
+/// consecutive records are retained. Records that were only partially
+/// written, e.g., because the process crashed, fail their checksum and
+/// are discarded, and so are older records that they overwrote.
+///
+/// The file must not be modified by other means while it is open.
+///
+/// # Examples
+/// ```rust
+/// # use rbuf::RecordLog;
+/// # let dir = tempfile::tempdir().unwrap();
+/// # let path = dir.path().join("log");
+/// let mut log = RecordLog::open(&path, 60).unwrap();
+/// for record in ["first", "second", "third"] {
+///   let _seq = log.push(record.as_bytes());
+/// }
+/// drop(log);
+///
+/// let log = RecordLog::open(&path, 60).unwrap();
+/// let records = log.iter().map(|record| record.data).collect::<Vec<_>>();
+/// assert_eq!(records, vec![&b"second"[..], &b"third"[..]]);
+/// ```
+pub struct RecordLog {
+  /// The mapped file, including the header.
+  mmap: MmapMut,
+  /// The offset of the oldest record in the data area.
+  head: usize,
+  /// The offset of the newest record in the data area.
+  last: usize,
+  /// The offset in the data area at which to write the next record.
+  tail: usize,
+  /// The sequence number of the oldest record.
+  first_seq: u64,
+  /// The number of records contained.
+  count: usize,
+}
+
+impl RecordLog {
+  /// Open the record log file at `path`, creating it with a data area
+  /// of `capacity` bytes if it does not exist, is empty, or has a
+  /// zeroed header as left behind by an interrupted creation.
+  ///
+  /// When opening an existing file, its header has to match `capacity`.
+  /// An error of kind [`ErrorKind::InvalidData`] is reported otherwise.
+  ///
+  /// # Panics
+  /// This constructor panics if `capacity` is too small to hold a
+  /// single record header.
+  pub fn open<P>(path: P, capacity: usize) -> io::Result<Self>
+  where
+    P: AsRef<Path>,
+  {
+    assert!(capacity > RECORD_HEADER_LEN);
+
+    let file_len = capacity
+      .checked_add(HEADER_LEN)
+      .and_then(|size| u64::try_from(size).ok())
+      .ok_or_else(|| io::Error::from(ErrorKind::OutOfMemory))?;
+
+    let file = OpenOptions::new()
+      .read(true)
+      .write(true)
+      .create(true)
+      .truncate(false)
+      .open(path)?;
+
+    let existing = file.metadata()?.len();
+    if existing == 0 {
+      let () = file.set_len(file_len)?;
+    } else if existing != file_len {
+      return Err(invalid_data("record log file has unexpected size"))
+    }
+
+    // SAFETY: We assume that the file is not modified by anybody else
+    //         while mapped, as documented.
+    let mut mmap = unsafe { MmapMut::map_mut(&file)? };
+    // A header consisting of zeros only belongs to a file that was just
+    // sized or one for which a crash happened before the header got
+    // persisted. Either way, the log is empty.
+    if mmap[..HEADER_LEN].iter().all(|byte| *byte == 0) {
+      let header = &mut mmap[..HEADER_LEN];
+      let () = header[VERSION_RANGE].copy_from_slice(&VERSION.to_le_bytes());
+      let () = header[CAPACITY_RANGE].copy_from_slice(&(capacity as u64).to_le_bytes());
+      // Write the magic last, so that a header left incomplete by a
+      // crash is never mistaken for a valid one.
+      let () = header[MAGIC_RANGE].copy_from_slice(&MAGIC);
+      let () = mmap.flush()?;
+    } else {
+      let header = &mmap[..HEADER_LEN];
+      if header[MAGIC_RANGE] != MAGIC {
+        return Err(invalid_data("file is not a record log file"))
+      }
+      if read_u32(header, VERSION_RANGE) != VERSION {
+        return Err(invalid_data("record log file has unsupported version"))
+      }
+      if read_u64(header, CAPACITY_RANGE) != capacity as u64 {
+        return Err(invalid_data("record log file has mismatching capacity"))
+      }
+    }
+
+    let mut slf = Self {
+      mmap,
+      head: 0,
+      last: 0,
+      tail: 0,
+      first_seq: 0,
+      count: 0,
+    };
+    let () = slf.recover();
+    Ok(slf)
+  }
+
+  /// Reconstruct the log's state from the records in the data area.
+  fn recover(&mut self) {
+    let data = self.data();
+
+    // The newest records, if the log ever wrapped around, or all of
+    // them otherwise, start at the beginning of the data area.
+    let newest = Chain::follow(data, 0);
+    // Any older records follow later, possibly after a region of
+    // records that got overwritten or were only partially written.
+    // Scan for the first chain that extends to the wrap around point
+    // and that is directly followed by the newest records.
+    let mut off = newest.end;
+    let older = loop {
+      if off >= data.len() {
+        break None
+      }
+
+      off = match slot(data, off) {
+        Slot::Record { .. } => {
+          let chain = Chain::follow(data, off);
+          if chain.wraps && (newest.count == 0 || chain.next_seq() == newest.first_seq) {
+            break Some(chain)
+          }
+          // Any record written after the ones of this chain would have
+          // ended it where the two overlap. Hence, the chain we are
+          // looking for cannot start before its end and we skip over
+          // it instead of checksumming its contents over and over.
+          chain.end
+        },
+        Slot::Wrap | Slot::Invalid => off + 1,
+      };
+    };
+
+    match (newest.count, older) {
+      (0, None) => (),
+      (0, Some(older)) => {
+        self.head = older.start;
+        self.last = older.last;
+        self.tail = 0;
+        self.first_seq = older.first_seq;
+        self.count = older.count;
+      },
+      (_, None) => {
+        self.head = 0;
+        self.last = newest.last;
+        self.tail = newest.end;
+        self.first_seq = newest.first_seq;
+        self.count = newest.count;
+      },
+      (_, Some(older)) => {
+        self.head = older.start;
+        self.last = newest.last;
+        self.tail = newest.end;
+        self.first_seq = older.first_seq;
+        self.count = older.count + newest.count;
+      },
+    }
+  }
+
+  /// Retrieve the data area.
+  #[inline]
+  fn data(&self) -> &[u8] {
+    &self.mmap[HEADER_LEN..]
+  }
+
+  /// Retrieve the data area mutably.
+  #[inline]
+  fn data_mut(&mut self) -> &mut [u8] {
+    &mut self.mmap[HEADER_LEN..]
+  }
+
+  /// Discard the oldest record.
+  fn evict(&mut self) {
+    debug_assert!(self.count > 0);
+
+    let data = self.data();
+    let head = self.head + RECORD_HEADER_LEN + record_at(data, self.head).data.len();
+    let wraps = wraps(data, head);
+
+    self.count -= 1;
+    self.first_seq += 1;
+    self.head = if self.count == 0 {
+      self.tail
+    } else if wraps {
+      0
+    } else {
+      head
+    };
+  }
+
+  /// Retrieve the size of the data area, in bytes.
+  #[inline]
+  pub fn capacity(&self) -> usize {
+    self.data().len()
+  }
+
+  /// Retrieve the maximum length of a record's payload.
+  #[inline]
+  pub fn max_record_len(&self) -> usize {
+    (self.capacity() - RECORD_HEADER_LEN).min(PAD as usize - 1)
+  }
+
+  /// Retrieve the number of records contained.
+  #[inline]
+  pub fn len(&self) -> usize {
+    self.count
+  }
+
+  /// Check whether the log contains no records.
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.count == 0
+  }
+
+  /// Retrieve the sequence number that the next record pushed will
+  /// receive.
+  #[inline]
+  pub fn next_seq(&self) -> u64 {
+    self.first_seq + self.count as u64
+  }
+
+  /// Append a record to the log, overwriting the oldest records as
+  /// necessary to make room for it.
+  ///
+  /// The record's sequence number is returned.
+  ///
+  /// # Panics
+  /// This method panics if the record is larger than
+  /// [`RecordLog::max_record_len`].
+  pub fn push(&mut self, record: &[u8]) -> u64 {
+    assert!(
+      record.len() <= self.max_record_len(),
+      "record of {} bytes exceeds maximum length of {}",
+      record.len(),
+      self.max_record_len()
+    );
+
+    let cap = self.capacity();
+    let size = RECORD_HEADER_LEN + record.len();
+    if cap - self.tail < size {
+      // Records never get split, so skip the remainder of the data
+      // area, which means discarding all records stored there.
+      while self.count > 0 && self.head >= self.tail {
+        let () = self.evict();
+      }
+
+      if cap - self.tail >= RECORD_HEADER_LEN {
+        let tail = self.tail;
+        let () = self.data_mut()[tail..tail + 4].copy_from_slice(&PAD.to_le_bytes());
+      }
+      self.tail = 0;
+      if self.count == 0 {
+        self.head = 0;
+      }
+    }
+
+    while self.count > 0 && self.head >= self.tail && self.head < self.tail + size {
+      let () = self.evict();
+    }
+
+    let seq = self.next_seq();
+    let len = (record.len() as u32).to_le_bytes();
+    let seq_bytes = seq.to_le_bytes();
+    let crc = checksum(&len, &seq_bytes, record).to_le_bytes();
+
+    let tail = self.tail;
+    let slot = &mut self.data_mut()[tail..tail + size];
+    let () = slot[LEN_RANGE].copy_from_slice(&len);
+    let () = slot[CRC_RANGE].copy_from_slice(&crc);
+    let () = slot[SEQ_RANGE].copy_from_slice(&seq_bytes);
+    let () = slot[RECORD_HEADER_LEN..].copy_from_slice(record);
+
+    if self.count == 0 {
+      self.head = tail;
+      self.first_seq = seq;
+    }
+    self.count += 1;
+    self.last = tail;
+    self.tail = tail + size;
+    seq
+  }
+
+  /// Retrieve the oldest record, if any.
+  #[inline]
+  pub fn front(&self) -> Option<Record<'_>> {
+    self.iter().next()
+  }
+
+  /// Retrieve the newest record, if any.
+  #[inline]
+  pub fn back(&self) -> Option<Record<'_>> {
+    if self.count == 0 {
+      return None
+    }
+    Some(record_at(self.data(), self.last))
+  }
+
+  /// Retrieve an iterator over the records, from oldest to newest.
+  #[inline]
+  pub fn iter(&self) -> RecordIter<'_> {
+    RecordIter {
+      data: self.data(),
+      off: self.head,
+      count: self.count,
+    }
+  }
+
+  /// Synchronously write all outstanding modifications back to the
+  /// file.
+  #[inline]
+  pub fn flush(&self) -> io::Result<()> {
+    self.mmap.flush()
+  }
+
+  /// Initiate writing back all outstanding modifications to the file,
+  /// without waiting for the operation to complete.
+  #[inline]
+  pub fn flush_async(&self) -> io::Result<()> {
+    self.mmap.flush_async()
+  }
+}
+
+impl Debug for RecordLog {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.debug_struct("RecordLog")
+      .field("capacity", &self.capacity())
+      .field("head", &self.head)
+      .field("last", &self.last)
+      .field("tail", &self.tail)
+      .field("first_seq", &self.first_seq)
+      .field("count", &self.count)
+      .finish()
+  }
+}
+
+impl<'log> IntoIterator for &'log RecordLog {
+  type Item = Record<'log>;
+  type IntoIter = RecordIter<'log>;
+
+  #[inline]
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
@@ -0,0 +1,310 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use core::iter::FusedIterator;
+
+
+/// The size of a record's length prefix, in bytes.
+const PREFIX_LEN: usize = 4;
+
+
+/// Read the payload of the record starting at `off`.
+#[inline]
+fn record_at(data: &[u8], off: usize) -> &[u8] {
+  let mut prefix = [0; PREFIX_LEN];
+  let () = prefix.copy_from_slice(&data[off..off + PREFIX_LEN]);
+  let len = u32::from_le_bytes(prefix) as usize;
+  let start = off + PREFIX_LEN;
+  &data[start..start + len]
+}
+
+
+/// An iterator over the records of a [`RecordRing`], from oldest to
+/// newest.
+#[derive(Clone)]
+pub struct RecordRingIter<'ring> {
+  /// The ring buffer's data.
+  data: &'ring [u8],
+  /// The offset of the next record to yield.
+  off: usize,
+  /// The end of the segment containing the oldest records.
+  end: usize,
+  /// The number of records left to yield.
+  count: usize,
+}
+
+impl<'ring> Iterator for RecordRingIter<'ring> {
+  type Item = &'ring [u8];
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.count == 0 {
+      return None
+    }
+
+    if self.off == self.end {
+      self.off = 0;
+    }
+
+    let record = record_at(self.data, self.off);
+    self.off += PREFIX_LEN + record.len();
+    self.count -= 1;
+    Some(record)
+  }
+
+  #[inline]
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    (self.count, Some(self.count))
+  }
+}
+
+impl ExactSizeIterator for RecordRingIter<'_> {}
+
+impl FusedIterator for RecordRingIter<'_> {}
+
+impl Debug for RecordRingIter<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.debug_struct("RecordRingIter")
+      .field("off", &self.off)
+      .field("count", &self.count)
+      .finish()
+  }
+}
+
+
+/// A ring buffer of variable length byte records.
+///
+/// Records are stored back-to-back in a single byte buffer, each
+/// preceded by a four byte length prefix. Pushing a record evicts as
+/// many of the oldest records as necessary to make room for it. A
+/// record never gets split across the end of the buffer: if it does not
+/// fit into the space remaining there, it is placed at the start
+/// instead, leaving the remainder unused (a layout commonly known as
+/// bip buffer). As a result, every record is accessible as a single
+/// slice.
+///
+/// Compared to, say, a [`RingBuf<String>`][crate::RingBuf], no per
+/// record allocation is necessary.
+///
+/// # Examples
+/// ```rust
+/// # use rbuf::RecordRing;
+/// let mut ring = RecordRing::new(16);
+/// assert_eq!(ring.push(b"abc"), 0);
+/// assert_eq!(ring.push(b"defg"), 0);
+/// // The record does not fit into the remaining space and wraps
+/// // around, evicting the oldest one.
+/// assert_eq!(ring.push(b"hi"), 1);
+/// assert_eq!(
+///   ring.iter().collect::<Vec<_>>(),
+///   vec![&b"defg"[..], &b"hi"[..]]
+/// );
+/// ```
+#[derive(Clone)]
+pub struct RecordRing {
+  /// Our actual data.
+  data: Box<[u8]>,
+  /// The offset of the oldest record.
+  head: usize,
+  /// The end of the segment starting at `head`.
+  ///
+  /// When not wrapped, this is the same as `tail`.
+  end: usize,
+  /// The offset at which the next record is written.
+  tail: usize,
+  /// The offset of the newest record.
+  last: usize,
+  /// Whether the newer records have been wrapped around to the start of
+  /// the buffer, i.e., whether records are located in `head..end` as
+  /// well as `0..tail`.
+  wrapped: bool,
+  /// The number of records stored.
+  count: usize,
+}
+
+impl RecordRing {
+  /// Create a new, empty `RecordRing` occupying `capacity` bytes.
+  ///
+  /// Each record requires four bytes for its length prefix in addition
+  /// to its payload.
+  ///
+  /// # Panics
+  /// This constructor panics if `capacity` is less than four.
+  pub fn new(capacity: usize) -> Self {
+    assert!(
+      capacity >= PREFIX_LEN,
+      "capacity {capacity} is too small to hold any record"
+    );
+
+    Self {
+      data: vec![0; capacity].into_boxed_slice(),
+      head: 0,
+      end: 0,
+      tail: 0,
+      last: 0,
+      wrapped: false,
+      count: 0,
+    }
+  }
+
+  /// Retrieve the ring buffer's capacity, in bytes.
+  #[inline]
+  pub fn capacity(&self) -> usize {
+    self.data.len()
+  }
+
+  /// Retrieve the size of the largest record that can be stored.
+  #[inline]
+  pub fn max_record_len(&self) -> usize {
+    (self.capacity() - PREFIX_LEN).min(u32::MAX as usize)
+  }
+
+  /// Retrieve the number of records stored.
+  #[inline]
+  pub const fn len(&self) -> usize {
+    self.count
+  }
+
+  /// Check whether no records are stored.
+  #[inline]
+  pub const fn is_empty(&self) -> bool {
+    self.count == 0
+  }
+
+  /// Evict the oldest record.
+  fn evict(&mut self) {
+    let len = record_at(&self.data, self.head).len();
+    self.head += PREFIX_LEN + len;
+    self.count -= 1;
+
+    if self.wrapped && self.head == self.end {
+      // The older segment is exhausted, leaving only the one at the
+      // start of the buffer.
+      self.head = 0;
+      self.end = self.tail;
+      self.wrapped = false;
+    }
+  }
+
+  /// Push a record, evicting as many of the oldest records as
+  /// necessary to make room for it.
+  ///
+  /// The number of records evicted is returned.
+  ///
+  /// # Panics
+  /// This method panics if `record` is larger than
+  /// [`RecordRing::max_record_len`].
+  pub fn push(&mut self, record: &[u8]) -> usize {
+    let max = self.max_record_len();
+    assert!(
+      record.len() <= max,
+      "record of {} bytes exceeds maximum record length {max}",
+      record.len()
+    );
+
+    let size = PREFIX_LEN + record.len();
+    let count = self.count;
+    let off = loop {
+      if self.count == 0 {
+        let () = self.clear();
+      }
+
+      if !self.wrapped {
+        if self.capacity() - self.tail >= size {
+          let off = self.tail;
+          self.tail += size;
+          self.end = self.tail;
+          break off
+        }
+
+        // The record does not fit into the space remaining at the end
+        // of the buffer. Continue at its start.
+        self.tail = 0;
+        self.wrapped = true;
+        continue
+      }
+
+      if self.head - self.tail >= size {
+        let off = self.tail;
+        self.tail += size;
+        break off
+      }
+
+      let () = self.evict();
+    };
+
+    // The length is known to fit, as checked above.
+    let prefix = (record.len() as u32).to_le_bytes();
+    let () = self.data[off..off + PREFIX_LEN].copy_from_slice(&prefix);
+    let () = self.data[off + PREFIX_LEN..off + size].copy_from_slice(record);
+    self.last = off;
+    self.count += 1;
+
+    // One record got added after all evictions.
+    count + 1 - self.count
+  }
+
+  /// Retrieve the oldest record.
+  #[inline]
+  pub fn front(&self) -> Option<&[u8]> {
+    if self.is_empty() {
+      return None
+    }
+    Some(record_at(&self.data, self.head))
+  }
+
+  /// Retrieve the newest record.
+  #[inline]
+  pub fn back(&self) -> Option<&[u8]> {
+    if self.is_empty() {
+      return None
+    }
+    Some(record_at(&self.data, self.last))
+  }
+
+  /// Retrieve an iterator over the records, from oldest to newest.
+  #[inline]
+  pub fn iter(&self) -> RecordRingIter<'_> {
+    RecordRingIter {
+      data: &self.data,
+      off: self.head,
+      end: self.end,
+      count: self.count,
+    }
+  }
+
+  /// Remove all records.
+  #[inline]
+  pub fn clear(&mut self) {
+    self.head = 0;
+    self.end = 0;
+    self.tail = 0;
+    self.last = 0;
+    self.wrapped = false;
+    self.count = 0;
+  }
+}
+
+impl Debug for RecordRing {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.debug_struct("RecordRing")
+      .field("capacity", &self.capacity())
+      .field("records", &self.iter().collect::<Vec<_>>())
+      .finish()
+  }
+}
+
+impl<'ring> IntoIterator for &'ring RecordRing {
+  type Item = &'ring [u8];
+  type IntoIter = RecordRingIter<'ring>;
+
+  #[inline]
+  fn into_iter(self) -> Self::IntoIter {
+    self.iter()
+  }
+}
@@ -1,13 +1,26 @@
 // Copyright (C) 2021-2025 Daniel Mueller <deso@posteo.net>
 // SPDX-License-Identifier: (Apache-2.0 OR MIT)
 
-use std::mem::size_of;
-use std::mem::take;
-use std::ops::Index;
-use std::ops::IndexMut;
-
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use core::marker::PhantomData;
+use core::mem::size_of;
+use core::mem::take;
+use core::ops::Index;
+use core::ops::IndexMut;
+use core::ops::Range;
+#[cfg(feature = "std")]
+use std::io::IoSlice;
+#[cfg(feature = "std")]
+use std::io::IoSliceMut;
+
+use crate::util::split_range;
 use crate::RingIter;
 use crate::RingIterMut;
+use crate::Storage;
 
 
 /// A ring buffer for arbitrary but default-initializable data.
@@ -16,7 +29,8 @@ use crate::RingIterMut;
 /// representations of the given type if nothing else has been inserted.
 /// There is no concept of removing elements, only overwriting them with
 /// the default. Gaps or non-existent elements can be represented by
-/// having an element type `Option<T>`.
+/// having an element type `Option<T>`, for which a set of helper
+/// methods (e.g., [`RingBuf::iter_some`]) is provided.
 ///
 /// One implication of the above is that iteration will always yield as
 /// many elements as the ring buffer's size.
@@ -26,14 +40,48 @@ use crate::RingIterMut;
 /// index of `self.len() - 1` the back one. Furthermore, indexes wrap
 /// around at the ring buffer's end, meaning that an index of value
 /// `self.len()` would access the front element as well.
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub struct RingBuf<T> {
+///
+/// By default, the ring buffer's data are stored in a boxed slice.
+/// However, any [`Storage`] can be used instead, be it a borrowed
+/// `&mut [T]`, a `Vec<T>`, an array, or a memory mapped region.
+///
+/// ```rust
+/// # use rbuf::RingBuf;
+/// let mut storage = [1, 2, 3];
+/// let mut buf = RingBuf::from_storage(&mut storage[..]);
+/// let () = buf.push_back(4);
+/// assert_eq!(*buf.front(), 2);
+/// drop(buf);
+/// assert_eq!(storage, [4, 2, 3]);
+/// ```
+#[derive(Clone)]
+pub struct RingBuf<T, S = Box<[T]>> {
   /// Our actual data.
-  data: Box<[T]>,
-  /// The index of the front element.
+  data: S,
+  /// The position of the front element, which the storage maps onto an
+  /// index.
   front: usize,
+  /// The length of our data.
+  len: usize,
+  /// Phantom data for our element type.
+  _phantom: PhantomData<T>,
 }
 
+/// A ring buffer storing its data inline, in an array of `N` elements.
+///
+/// A `RingArray` does not require a heap allocation. It can be
+/// constructed in `const` contexts, which makes it usable in `static`
+/// variables.
+///
+/// ```rust
+/// # use rbuf::RingArray;
+/// static RING: RingArray<u32, 4> = RingArray::from_array([1, 2, 3, 4]);
+/// assert_eq!(*RING.front(), 1);
+/// assert_eq!(*RING.back(), 4);
+/// ```
+pub type RingArray<T, const N: usize> = RingBuf<T, [T; N]>;
+
+
 impl<T> RingBuf<T>
 where
   T: Default,
@@ -49,6 +97,54 @@ where
     Self::from_vec(vec)
   }
 
+  /// Convert the `RingBuf` into a boxed slice of its contents.
+  ///
+  /// The slice's first element will represents the (former) ring
+  /// buffer's front its last element the buffer's back.
+  pub fn into_boxed_slice(self) -> Box<[T]> {
+    self.into_storage()
+  }
+}
+
+impl<T> RingBuf<T> {
+  /// Create a new `RingBuf` with data from a `Vec`.
+  ///
+  /// Note that the vector's first element is considered the front.
+  ///
+  /// # Panics
+  /// This constructor panics if the provided vector is empty.
+  #[inline]
+  pub fn from_vec(vec: Vec<T>) -> Self {
+    Self::from(vec.into_boxed_slice())
+  }
+}
+
+impl<T, const N: usize> RingBuf<T, [T; N]> {
+  /// Create a new `RingBuf` with data from an array, stored inline.
+  ///
+  /// Note that the array's first element is considered the front.
+  ///
+  /// # Panics
+  /// This constructor panics if `N` is zero. When evaluated in a
+  /// `const` context, that results in a compilation error.
+  #[inline]
+  pub const fn from_array(data: [T; N]) -> Self {
+    assert!(N != 0, "a ring buffer needs to contain at least one element");
+
+    Self {
+      data,
+      front: 0,
+      len: N,
+      _phantom: PhantomData,
+    }
+  }
+}
+
+impl<T, S> RingBuf<T, S>
+where
+  T: Default,
+  S: Storage<T>,
+{
   /// Pop the front element from the ring buffer.
   ///
   /// This operation will remove the ring buffer's front element and
@@ -56,15 +152,8 @@ where
   /// current front will become the new front.
   pub fn pop_front(&mut self) -> T {
     let idx = self.front_idx();
-    self.front = (idx + 1) % self.len();
-
-    #[cfg(debug_assertions)]
-    let front = take(self.data.get_mut(idx).unwrap());
-    #[cfg(not(debug_assertions))]
-    // SAFETY: The index is within the bounds of the underlying slice.
-    let front = take(unsafe { self.data.get_unchecked_mut(idx) });
-
-    front
+    self.front = S::advance(self.front, 1, self.len());
+    take(self.slot_mut(idx))
   }
 
   /// Pop the back element from the ring buffer.
@@ -74,38 +163,86 @@ where
   /// current back will become the new back.
   pub fn pop_back(&mut self) -> T {
     let idx = self.back_idx();
-    self.front = idx;
+    self.front = S::retreat(self.front, 1, self.len());
+    take(self.slot_mut(idx))
+  }
+}
 
-    #[cfg(debug_assertions)]
-    let back = take(self.data.get_mut(idx).unwrap());
-    #[cfg(not(debug_assertions))]
-    // SAFETY: The index is within the bounds of the underlying slice.
-    let back = take(unsafe { self.data.get_unchecked_mut(idx) });
+#[allow(clippy::len_without_is_empty)]
+impl<T, S> RingBuf<T, S>
+where
+  S: Storage<T>,
+{
+  /// Create a new `RingBuf` operating on the provided storage.
+  ///
+  /// Note that the storage's first element is considered the front.
+  ///
+  /// # Panics
+  /// This constructor panics if the provided storage is empty.
+  #[inline]
+  pub fn from_storage(storage: S) -> Self {
+    let len = storage.as_ref().len();
+    assert!(len != 0);
 
-    back
+    Self {
+      data: storage,
+      front: 0,
+      len,
+      _phantom: PhantomData,
+    }
   }
 
-  /// Convert the `RingBuf` into a boxed slice of its contents.
+  /// Convert the `RingBuf` into its underlying storage.
   ///
-  /// The slice's first element will represents the (former) ring
-  /// buffer's front its last element the buffer's back.
-  pub fn into_boxed_slice(mut self) -> Box<[T]> {
+  /// The storage is rearranged first, such that its first element
+  /// represents the (former) ring buffer's front and its last element
+  /// the buffer's back.
+  #[inline]
+  pub fn into_storage(mut self) -> S {
     let _data = self.make_contiguous();
     self.data
   }
-}
 
-#[allow(clippy::len_without_is_empty)]
-impl<T> RingBuf<T> {
-  /// Create a new `RingBuf` with data from a `Vec`.
-  ///
-  /// Note that the vector's first element is considered the front.
-  ///
-  /// # Panics
-  /// This constructor panics if the provided vector is empty.
+  /// Retrieve the underlying storage as a slice.
   #[inline]
-  pub fn from_vec(vec: Vec<T>) -> Self {
-    Self::from(vec.into_boxed_slice())
+  fn data(&self) -> &[T] {
+    self.data.as_ref()
+  }
+
+  /// Retrieve the underlying storage as a mutable slice.
+  #[inline]
+  fn data_mut(&mut self) -> &mut [T] {
+    self.data.as_mut()
+  }
+
+  /// Retrieve the element at the provided index into the underlying
+  /// storage.
+  #[inline]
+  fn slot(&self, idx: usize) -> &T {
+    #[cfg(debug_assertions)]
+    let elem = self.data().get(idx).unwrap();
+    #[cfg(not(debug_assertions))]
+    // SAFETY: The index is within the bounds of the underlying slice,
+    //         the length of which is guaranteed to be constant by
+    //         `Storage` implementations.
+    let elem = unsafe { self.data().get_unchecked(idx) };
+
+    elem
+  }
+
+  /// Retrieve the element at the provided index into the underlying
+  /// storage.
+  #[inline]
+  fn slot_mut(&mut self, idx: usize) -> &mut T {
+    #[cfg(debug_assertions)]
+    let elem = self.data_mut().get_mut(idx).unwrap();
+    #[cfg(not(debug_assertions))]
+    // SAFETY: The index is within the bounds of the underlying slice,
+    //         the length of which is guaranteed to be constant by
+    //         `Storage` implementations.
+    let elem = unsafe { self.data_mut().get_unchecked_mut(idx) };
+
+    elem
   }
 
   /// Rearrange the internal storage of the ring buffer so it is one
@@ -113,41 +250,108 @@ impl<T> RingBuf<T> {
   /// back the last one.
   #[inline]
   pub fn make_contiguous(&mut self) -> &mut [T] {
-    let () = self.data.rotate_left(self.front);
+    let front = self.front_idx();
+    let () = self.data_mut().rotate_left(front);
     self.front = 0;
-    &mut self.data
+    self.data_mut()
+  }
+
+  /// Rotate the ring buffer `n` places to the left, making the element
+  /// at index `n` the new front.
+  ///
+  /// Contrary to [`VecDeque::rotate_left`][alloc::collections::VecDeque::rotate_left],
+  /// this operation is O(1), as it only has to adjust the front index.
+  ///
+  /// # Panics
+  /// This method panics if `n` is greater than the ring buffer's
+  /// length.
+  #[inline]
+  pub fn rotate_left(&mut self, n: usize) {
+    let len = self.len();
+    assert!(n <= len, "rotation by {n} exceeds ring buffer length {len}");
+    self.front = S::advance(self.front, n, len);
+  }
+
+  /// Rotate the ring buffer `n` places to the right, making the element
+  /// at index `len - n` the new front.
+  ///
+  /// Contrary to [`VecDeque::rotate_right`][alloc::collections::VecDeque::rotate_right],
+  /// this operation is O(1), as it only has to adjust the front index.
+  ///
+  /// # Panics
+  /// This method panics if `n` is greater than the ring buffer's
+  /// length.
+  #[inline]
+  pub fn rotate_right(&mut self, n: usize) {
+    let len = self.len();
+    assert!(n <= len, "rotation by {n} exceeds ring buffer length {len}");
+    self.front = S::retreat(self.front, n, len);
+  }
+
+  /// Retrieve the contents of the ring buffer as a pair of slices.
+  ///
+  /// The first slice starts with the front element, the second one ends
+  /// with the back element. Either of them may be empty.
+  #[inline]
+  pub fn as_slices(&self) -> (&[T], &[T]) {
+    let (back, front) = self.data().split_at(self.front_idx());
+    (front, back)
+  }
+
+  /// Retrieve the contents of the ring buffer as a pair of mutable
+  /// slices.
+  ///
+  /// The first slice starts with the front element, the second one ends
+  /// with the back element. Either of them may be empty.
+  #[inline]
+  pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+    let front = self.front_idx();
+    let (back, front) = self.data_mut().split_at_mut(front);
+    (front, back)
+  }
+
+  /// Retrieve the elements in the provided range of logical indexes as
+  /// a pair of slices.
+  ///
+  /// The range has to be a subset of `0..self.len()`.
+  #[inline]
+  pub(crate) fn range_slices(&self, range: Range<usize>) -> (&[T], &[T]) {
+    let (first, second) = split_range(self.len(), self.front_idx(), range);
+    let data = self.data();
+    (&data[first], &data[second])
+  }
+
+  /// Retrieve the elements in the provided range of logical indexes as
+  /// a pair of mutable slices.
+  ///
+  /// The range has to be a subset of `0..self.len()`.
+  #[inline]
+  pub(crate) fn range_slices_mut(&mut self, range: Range<usize>) -> (&mut [T], &mut [T]) {
+    let (first, second) = split_range(self.len(), self.front_idx(), range);
+    // The second range, if not empty, always lies before the first one
+    // in storage.
+    let (lower, upper) = self.data_mut().split_at_mut(first.start);
+    (&mut upper[..first.len()], &mut lower[second])
   }
 
   /// Retrieve the ring buffer's length.
   #[inline]
   pub const fn len(&self) -> usize {
-    self.data.len()
+    self.len
   }
 
   /// Retrieve the current front element.
   #[inline]
   pub fn front(&self) -> &T {
     let idx = self.front_idx();
-    #[cfg(debug_assertions)]
-    let front = self.data.get(idx).unwrap();
-    #[cfg(not(debug_assertions))]
-    // SAFETY: The index is within the bounds of the underlying slice.
-    let front = unsafe { self.data.get_unchecked(idx) };
-
-    front
+    self.slot(idx)
   }
 
   /// Retrieve the current front element.
   #[inline]
   pub fn front_mut(&mut self) -> &mut T {
     let idx = self.front_idx();
-    #[cfg(debug_assertions)]
-    let front = self.data.get_mut(idx).unwrap();
-    #[cfg(not(debug_assertions))]
-    // SAFETY: The index is within the bounds of the underlying slice.
-    let front = unsafe { self.data.get_unchecked_mut(idx) };
-
-    front
+    self.slot_mut(idx)
   }
 
   /// Retrieve the current front index.
@@ -158,33 +362,21 @@ impl<T> RingBuf<T> {
   /// implementation (as accessible through bracket syntax).
   #[inline]
   fn front_idx(&self) -> usize {
-    self.front
+    S::index(self.front, self.len())
   }
 
   /// Retrieve the current back element.
   #[inline]
   pub fn back(&self) -> &T {
     let idx = self.back_idx();
-    #[cfg(debug_assertions)]
-    let back = self.data.get(idx).unwrap();
-    #[cfg(not(debug_assertions))]
-    // SAFETY: The index is within the bounds of the underlying slice.
-    let back = unsafe { self.data.get_unchecked(idx) };
-
-    back
+    self.slot(idx)
   }
 
   /// Retrieve the current back element.
   #[inline]
   pub fn back_mut(&mut self) -> &mut T {
     let idx = self.back_idx();
-    #[cfg(debug_assertions)]
-    let back = self.data.get_mut(idx).unwrap();
-    #[cfg(not(debug_assertions))]
-    // SAFETY: The index is within the bounds of the underlying slice.
-    let back = unsafe { self.data.get_unchecked_mut(idx) };
-
-    back
+    self.slot_mut(idx)
   }
 
   /// Retrieve the current back index.
@@ -195,7 +387,8 @@ impl<T> RingBuf<T> {
   /// implementation (as accessible through bracket syntax).
   #[inline]
   fn back_idx(&self) -> usize {
-    self.front.checked_sub(1).unwrap_or(self.len() - 1)
+    let len = self.len();
+    S::index(S::retreat(self.front, 1, len), len)
   }
 
   /// Push an element to the front of the ring buffer.
@@ -207,20 +400,9 @@ impl<T> RingBuf<T> {
   /// to the front entails a replacement of the back element.
   #[inline]
   pub fn push_front(&mut self, elem: T) {
-    let len = self.data.len();
     let idx = self.back_idx();
-    debug_assert!(idx < len, "idx: {idx}, len: {len}");
-
-    #[cfg(debug_assertions)]
-    {
-      *self.data.get_mut(idx).unwrap() = elem;
-    }
-    #[cfg(not(debug_assertions))]
-    // SAFETY: The index is within the bounds of the underlying slice.
-    unsafe {
-      *self.data.get_unchecked_mut(idx) = elem;
-    }
-    self.front = idx;
+    *self.slot_mut(idx) = elem;
+    self.front = S::retreat(self.front, 1, self.len());
   }
 
   /// Push an element to the back of the ring buffer.
@@ -232,28 +414,20 @@ impl<T> RingBuf<T> {
   /// to the back entails a replacement of the front element.
   #[inline]
   pub fn push_back(&mut self, elem: T) {
-    let len = self.data.len();
     let idx = self.front_idx();
-    debug_assert!(idx < len, "idx: {idx}, len: {len}");
-
-    #[cfg(debug_assertions)]
-    {
-      *self.data.get_mut(idx).unwrap() = elem;
-    }
-    #[cfg(not(debug_assertions))]
-    // SAFETY: The index is within the bounds of the underlying slice.
-    unsafe {
-      *self.data.get_unchecked_mut(idx) = elem;
-    }
-    self.front = (self.front + 1) % self.len();
+    *self.slot_mut(idx) = elem;
+    self.front = S::advance(self.front, 1, self.len());
   }
 
   /// Retrieve an iterator over the elements of the ring buffer.
   ///
   /// The iterator traverses the ring buffer in front-to-back manner.
   #[inline]
-  pub const fn iter(&self) -> RingIter<'_, T> {
-    RingIter::new(self)
+  pub fn iter(&self) -> RingIter<'_, T> {
+    let len = self.len;
+    // SAFETY: All `len` elements of our storage are initialized and
+    //         remain borrowed for the lifetime of the iterator.
+    unsafe { RingIter::from_raw_parts(self.data().as_ptr(), len, self.front_idx(), len) }
   }
 
   /// Retrieve a mutating iterator over the elements of the ring buffer.
@@ -270,37 +444,101 @@ impl<T> RingBuf<T> {
       "Mutable iterators are not supported on ring buffers over zero sized types"
     );
 
-    RingIterMut::new(self)
+    let front = self.front_idx();
+    let len = self.len;
+    let data = self.data_mut();
+    // SAFETY: All `len` elements of our storage are initialized and
+    //         remain exclusively borrowed for the lifetime of the
+    //         iterator.
+    unsafe { RingIterMut::from_raw_parts(data.as_mut_ptr(), len, front, len) }
+  }
+}
+
+#[cfg(feature = "std")]
+impl<S> RingBuf<u8, S>
+where
+  S: Storage<u8>,
+{
+  /// Retrieve the contents of the ring buffer as a pair of
+  /// [`IoSlice`]s, in logical order.
+  ///
+  /// The result can be passed to
+  /// [`Write::write_vectored`][std::io::Write::write_vectored] to write
+  /// out the ring buffer without rearranging or copying its data first.
+  #[inline]
+  pub fn io_slices(&self) -> [IoSlice<'_>; 2] {
+    let (first, second) = self.as_slices();
+    [IoSlice::new(first), IoSlice::new(second)]
+  }
+
+  /// Retrieve the contents of the ring buffer as a pair of
+  /// [`IoSliceMut`]s, in logical order.
+  ///
+  /// The result can be passed to
+  /// [`Read::read_vectored`][std::io::Read::read_vectored] to fill the
+  /// ring buffer without rearranging it or copying data afterwards.
+  #[inline]
+  pub fn io_slices_mut(&mut self) -> [IoSliceMut<'_>; 2] {
+    let (first, second) = self.as_mut_slices();
+    [IoSliceMut::new(first), IoSliceMut::new(second)]
   }
 }
 
-impl<T> Index<usize> for RingBuf<T> {
+impl<T, S> Debug for RingBuf<T, S>
+where
+  T: Debug,
+  S: Storage<T>,
+{
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.debug_struct("RingBuf")
+      .field("data", &self.data())
+      .field("front", &self.front_idx())
+      .finish()
+  }
+}
+
+/// Two `RingBuf` objects are considered equal if their storage contents
+/// and the index of their front elements match.
+impl<T, S> PartialEq for RingBuf<T, S>
+where
+  T: PartialEq,
+  S: Storage<T>,
+{
+  fn eq(&self, other: &Self) -> bool {
+    self.front_idx() == other.front_idx() && self.data() == other.data()
+  }
+}
+
+impl<T, S> Eq for RingBuf<T, S>
+where
+  T: Eq,
+  S: Storage<T>,
+{
+}
+
+impl<T, S> Index<usize> for RingBuf<T, S>
+where
+  S: Storage<T>,
+{
   type Output = T;
 
   #[inline]
   fn index(&self, idx: usize) -> &Self::Output {
-    let idx = (self.front_idx() + idx) % self.len();
-    #[cfg(debug_assertions)]
-    let elem = self.data.get(idx).unwrap();
-    #[cfg(not(debug_assertions))]
-    // SAFETY: The index is within the bounds of the underlying slice.
-    let elem = unsafe { self.data.get_unchecked(idx) };
-
-    elem
+    let len = self.len();
+    let idx = S::index(S::advance(self.front, idx, len), len);
+    self.slot(idx)
   }
 }
 
-impl<T> IndexMut<usize> for RingBuf<T> {
+impl<T, S> IndexMut<usize> for RingBuf<T, S>
+where
+  S: Storage<T>,
+{
   #[inline]
   fn index_mut(&mut self, idx: usize) -> &mut Self::Output {
-    let idx = (self.front_idx() + idx) % self.len();
-    #[cfg(debug_assertions)]
-    let elem = self.data.get_mut(idx).unwrap();
-    #[cfg(not(debug_assertions))]
-    // SAFETY: The index is within the bounds of the underlying slice.
-    let elem = unsafe { self.data.get_unchecked_mut(idx) };
-
-    elem
+    let len = self.len();
+    let idx = S::index(S::advance(self.front, idx, len), len);
+    self.slot_mut(idx)
   }
 }
 
@@ -311,11 +549,17 @@ impl<T> IndexMut<usize> for RingBuf<T> {
 impl<T> From<Box<[T]>> for RingBuf<T> {
   #[inline]
   fn from(other: Box<[T]>) -> Self {
-    assert!(!other.is_empty());
+    Self::from_storage(other)
+  }
+}
 
-    Self {
-      data: other,
-      front: 0,
-    }
+/// Create a `RingBuf` from an array.
+///
+/// # Panics
+/// This conversion panics if the provided array is empty.
+impl<T, const N: usize> From<[T; N]> for RingBuf<T, [T; N]> {
+  #[inline]
+  fn from(other: [T; N]) -> Self {
+    Self::from_array(other)
   }
 }
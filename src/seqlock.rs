@@ -0,0 +1,321 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+//! A single-writer ring buffer allowing for lock-free snapshot reads.
+//!
+//! A ring buffer is created by means of [`channel`], which returns a
+//! [`Writer`] and a [`Reader`]. The writer never blocks: it simply
+//! overwrites the oldest element when pushing a new one. Readers can
+//! be cloned and take consistent copies of individual elements or
+//! ranges of them, retrying when they race with the writer.
+//!
+//! Consistency is ensured by means of a sequence lock per slot: the
+//! writer marks a slot as being written before updating it and as
+//! stable afterwards. Readers check that the slot was stable and
+//! unchanged across their copy, discarding torn reads.
+//!
+//! # Examples
+//! ```rust
+//! use rbuf::seqlock::channel;
+//!
+//! let (mut writer, reader) = channel(3);
+//! for i in 0..5 {
+//!   let () = writer.push(i);
+//! }
+//!
+//! assert_eq!(reader.latest(), Some(4));
+//! assert_eq!(reader.get(1), None);
+//! assert_eq!(reader.get(2), Some(2));
+//! assert_eq!(reader.snapshot(2), vec![3, 4]);
+//! ```
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cell::UnsafeCell;
+use core::fmt::Debug;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use core::mem::MaybeUninit;
+use core::ops::Range;
+use core::ptr;
+// The seqlock protocol relies on racy reads that are subsequently
+// validated, which `loom` cannot model. Hence, we always use the
+// regular atomics here.
+use core::sync::atomic::fence;
+use core::sync::atomic::AtomicUsize;
+use core::sync::atomic::Ordering;
+
+use crate::sync::CachePadded;
+
+
+/// Retrieve the slot sequence number of an element that is stable.
+#[inline]
+fn stable(seq: usize) -> usize {
+  seq.wrapping_add(1).wrapping_mul(2)
+}
+
+/// Retrieve the slot sequence number of an element that is being
+/// written.
+#[inline]
+fn writing(seq: usize) -> usize {
+  seq.wrapping_mul(2).wrapping_add(1)
+}
+
+
+/// A slot of the ring buffer.
+struct Slot<T> {
+  /// The sequence lock protecting the slot.
+  ///
+  /// The value encodes the sequence number of the element stored in the
+  /// slot and whether it is currently being written (odd) or stable
+  /// (even).
+  seq: AtomicUsize,
+  /// The slot's element.
+  value: UnsafeCell<MaybeUninit<T>>,
+}
+
+
+/// The state shared between the writer and the readers.
+struct Shared<T> {
+  /// The sequence number of the next element to be pushed.
+  head: CachePadded<AtomicUsize>,
+  /// Our actual data.
+  slots: Box<[Slot<T>]>,
+}
+
+// SAFETY: Elements are only ever copied between threads and all access
+//         to slots is validated through their sequence locks.
+unsafe impl<T> Send for Shared<T> where T: Copy + Send {}
+// SAFETY: See above.
+unsafe impl<T> Sync for Shared<T> where T: Copy + Send {}
+
+impl<T> Shared<T>
+where
+  T: Copy,
+{
+  /// Retrieve the slot for the element with the provided sequence
+  /// number.
+  #[inline]
+  fn slot(&self, seq: usize) -> &Slot<T> {
+    &self.slots[seq % self.slots.len()]
+  }
+
+  /// Retrieve the element with the provided sequence number, if it is
+  /// still retained.
+  fn get(&self, seq: usize) -> Option<T> {
+    let slot = self.slot(seq);
+    let before = slot.seq.load(Ordering::Acquire);
+    if before != stable(seq) {
+      return None
+    }
+
+    // SAFETY: The pointer is valid for reads. The read may race with
+    //         the writer, in which case the value could be torn. We
+    //         only ever assume its initialization after validating the
+    //         sequence lock, though. As `T` is `Copy`, there is no drop
+    //         logic to worry about.
+    let value = unsafe { ptr::read_volatile(slot.value.get()) };
+    let () = fence(Ordering::Acquire);
+    let after = slot.seq.load(Ordering::Relaxed);
+    if after != before {
+      return None
+    }
+
+    // SAFETY: The slot contained the element throughout our read and
+    //         it was initialized when it became stable.
+    Some(unsafe { value.assume_init() })
+  }
+}
+
+
+/// Create a new seqlock-based ring buffer retaining the most recent
+/// `cap` elements.
+///
+/// # Panics
+/// This function panics if `cap` is zero.
+pub fn channel<T>(cap: usize) -> (Writer<T>, Reader<T>)
+where
+  T: Copy,
+{
+  assert_ne!(cap, 0);
+
+  let slots = (0..cap)
+    .map(|_| Slot {
+      seq: AtomicUsize::new(0),
+      value: UnsafeCell::new(MaybeUninit::uninit()),
+    })
+    .collect();
+  let shared = Arc::new(Shared {
+    head: CachePadded(AtomicUsize::new(0)),
+    slots,
+  });
+
+  let writer = Writer {
+    shared: Arc::clone(&shared),
+    head: 0,
+  };
+  let reader = Reader { shared };
+  (writer, reader)
+}
+
+
+/// The writing half of a seqlock-based ring buffer.
+pub struct Writer<T> {
+  /// The state shared with the readers.
+  shared: Arc<Shared<T>>,
+  /// A local copy of the shared head counter, which only we modify.
+  head: usize,
+}
+
+impl<T> Writer<T>
+where
+  T: Copy,
+{
+  /// Push an element into the ring buffer, overwriting the oldest one
+  /// if it is full.
+  ///
+  /// This operation never blocks.
+  pub fn push(&mut self, value: T) {
+    let seq = self.head;
+    let slot = self.shared.slot(seq);
+
+    let () = slot.seq.store(writing(seq), Ordering::Relaxed);
+    let () = fence(Ordering::Release);
+    // SAFETY: We are the only writer and the pointer is valid for
+    //         writes. Readers may race with us, but they validate
+    //         their reads through the slot's sequence lock.
+    let () = unsafe { ptr::write_volatile(slot.value.get(), MaybeUninit::new(value)) };
+    let () = slot.seq.store(stable(seq), Ordering::Release);
+
+    self.head = seq.wrapping_add(1);
+    let () = self.shared.head.store(self.head, Ordering::Release);
+  }
+
+  /// Retrieve the ring buffer's capacity.
+  #[inline]
+  pub fn capacity(&self) -> usize {
+    self.shared.slots.len()
+  }
+
+  /// Retrieve the total number of elements pushed so far, which is
+  /// also the sequence number of the next element to be pushed.
+  #[inline]
+  pub fn pushed(&self) -> usize {
+    self.head
+  }
+}
+
+impl<T> Debug for Writer<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.debug_struct("Writer")
+      .field("capacity", &self.shared.slots.len())
+      .field("pushed", &self.head)
+      .finish()
+  }
+}
+
+
+/// A reading half of a seqlock-based ring buffer.
+///
+/// Elements are identified by their sequence number, with the first
+/// element ever pushed having sequence number zero.
+pub struct Reader<T> {
+  /// The state shared with the writer and other readers.
+  shared: Arc<Shared<T>>,
+}
+
+impl<T> Reader<T>
+where
+  T: Copy,
+{
+  /// Retrieve the ring buffer's capacity.
+  #[inline]
+  pub fn capacity(&self) -> usize {
+    self.shared.slots.len()
+  }
+
+  /// Retrieve the total number of elements pushed so far, which is
+  /// also the sequence number of the next element to be pushed.
+  #[inline]
+  pub fn pushed(&self) -> usize {
+    self.shared.head.load(Ordering::Acquire)
+  }
+
+  /// Retrieve a copy of the element with the provided sequence number.
+  ///
+  /// `None` is returned if the element has not been pushed yet or if it
+  /// has been overwritten already.
+  #[inline]
+  pub fn get(&self, seq: usize) -> Option<T> {
+    self.shared.get(seq)
+  }
+
+  /// Retrieve a copy of the most recently pushed element.
+  pub fn latest(&self) -> Option<T> {
+    loop {
+      let head = self.pushed();
+      if head == 0 {
+        break None
+      }
+
+      if let Some(value) = self.get(head - 1) {
+        break Some(value)
+      }
+      // The writer overwrote the element while we were reading it.
+      // Try again with the new latest one.
+    }
+  }
+
+  /// Retrieve copies of the elements with sequence numbers in the
+  /// provided range, in order.
+  ///
+  /// `None` is returned if any of the elements has not been pushed yet
+  /// or has been overwritten already.
+  pub fn range(&self, range: Range<usize>) -> Option<Vec<T>> {
+    if range.len() > self.capacity() || range.end > self.pushed() {
+      return None
+    }
+
+    let mut values = Vec::with_capacity(range.len());
+    for seq in range {
+      let () = values.push(self.get(seq)?);
+    }
+    Some(values)
+  }
+
+  /// Retrieve copies of the up to `count` most recently pushed
+  /// elements, in order.
+  ///
+  /// The elements are guaranteed to be consecutive. If the writer
+  /// overwrites any of them while they are being copied, the snapshot
+  /// is retried with one element less. Hence, with a busy writer fewer
+  /// than `count` elements may be returned, even if more were pushed.
+  pub fn snapshot(&self, mut count: usize) -> Vec<T> {
+    loop {
+      let head = self.pushed();
+      count = count.min(head).min(self.capacity());
+      if let Some(values) = self.range(head - count..head) {
+        break values
+      }
+      // An empty range always succeeds, so this loop is bounded.
+      count -= 1;
+    }
+  }
+}
+
+impl<T> Clone for Reader<T> {
+  fn clone(&self) -> Self {
+    Self {
+      shared: Arc::clone(&self.shared),
+    }
+  }
+}
+
+impl<T> Debug for Reader<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.debug_struct("Reader")
+      .field("capacity", &self.shared.slots.len())
+      .finish()
+  }
+}
@@ -0,0 +1,662 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+//! A single-producer/single-consumer ring buffer in shared memory.
+//!
+//! A [`Producer`] creates a named POSIX shared memory object holding a
+//! ring buffer, which a [`Consumer`] in a different process can attach
+//! to by means of the same name. Elements are exchanged without any
+//! locking or system calls, coordinated only through atomic head and
+//! tail counters in the shared memory object's header. Because elements
+//! are copied between address spaces, they have to be [`Pod`].
+//!
+//! The consumer can check whether the producer is still around by means
+//! of [`Consumer::is_producer_alive`]. That works even if the producer
+//! process terminated abnormally.
+//!
+//! This module is only available on Linux.
+//!
+//! # Examples
+//! ```rust
+//! use rbuf::shm::Consumer;
+//! use rbuf::shm::Producer;
+//!
+//! let name = format!("/rbuf-example-{}", std::process::id());
+//! let mut producer = Producer::<u64>::create(&name, 4).unwrap();
+//! // Typically done in a different process.
+//! let mut consumer = Consumer::<u64>::attach(&name).unwrap();
+//!
+//! let () = producer.push(1).unwrap();
+//! let () = producer.push(2).unwrap();
+//! drop(producer);
+//!
+//! assert!(!consumer.is_producer_alive());
+//! assert_eq!(consumer.pop(), Some(1));
+//! assert_eq!(consumer.pop(), Some(2));
+//! assert_eq!(consumer.pop(), None);
+//! ```
+
+use core::fmt::Debug;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use core::marker::PhantomData;
+use core::mem::align_of;
+use core::mem::size_of;
+use core::ptr;
+use core::ptr::NonNull;
+use core::sync::atomic::AtomicU32;
+use core::sync::atomic::AtomicU64;
+use core::sync::atomic::Ordering;
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::fs::File;
+use std::io;
+use std::io::ErrorKind;
+use std::os::unix::fs::MetadataExt as _;
+use std::os::unix::io::AsRawFd as _;
+use std::os::unix::io::FromRawFd as _;
+
+use bytemuck::Pod;
+
+use crate::sys::check;
+
+
+/// The magic bytes identifying a shared memory ring buffer.
+const MAGIC: [u8; 8] = *b"RBUFSHM\0";
+/// The version of the memory layout.
+///
+/// The version is only set once the producer has initialized the
+/// header. Zero indicates that initialization is still in progress.
+const VERSION: u32 = 1;
+/// The size of the header, in bytes. Element data start right after
+/// it.
+const HEADER_LEN: usize = 192;
+
+/// The offset of the magic bytes in the header.
+const MAGIC_OFFSET: usize = 0;
+/// The offset of the layout version in the header.
+const VERSION_OFFSET: usize = 8;
+/// The offset of the element size in the header.
+const ELEM_SIZE_OFFSET: usize = 12;
+/// The offset of the capacity in the header.
+const CAPACITY_OFFSET: usize = 16;
+/// The offset of the head counter in the header. It lives on a cache
+/// line of its own, as it is modified by the producer.
+const HEAD_OFFSET: usize = 64;
+/// The offset of the tail counter in the header. It lives on a cache
+/// line of its own, as it is modified by the consumer.
+const TAIL_OFFSET: usize = 128;
+
+
+/// Create an error indicating that the shared memory object is not a
+/// valid ring buffer.
+#[inline]
+fn invalid_data(msg: &str) -> io::Error {
+  io::Error::new(ErrorKind::InvalidData, msg)
+}
+
+/// Create an error indicating that the shared memory object is still
+/// being initialized.
+#[inline]
+fn not_ready() -> io::Error {
+  io::Error::new(ErrorKind::WouldBlock, "ring buffer is not yet initialized")
+}
+
+/// Create an error indicating that the shared memory object is in use
+/// by another producer.
+#[inline]
+fn in_use() -> io::Error {
+  io::Error::new(
+    ErrorKind::AddrInUse,
+    "shared memory object is in use by another producer",
+  )
+}
+
+/// Convert `name` into a C string suitable for `shm_open`.
+fn c_name(name: &str) -> io::Result<CString> {
+  CString::new(name)
+    .map_err(|_err| io::Error::new(ErrorKind::InvalidInput, "name contains NUL byte"))
+}
+
+/// Calculate the size of a shared memory object holding `cap` elements
+/// of type `T`.
+fn object_size<T>(cap: usize) -> io::Result<usize> {
+  cap
+    .checked_mul(size_of::<T>())
+    .and_then(|size| size.checked_add(HEADER_LEN))
+    .ok_or_else(|| io::Error::from(ErrorKind::OutOfMemory))
+}
+
+/// Check that `T` can be stored in a shared memory ring buffer.
+fn check_type<T>() {
+  assert_ne!(size_of::<T>(), 0, "zero-sized types are not supported");
+  assert!(align_of::<T>() <= HEAD_OFFSET);
+}
+
+
+/// A shared memory object mapped into our address space.
+struct Mapping {
+  /// The start of the mapping.
+  ptr: NonNull<u8>,
+  /// The size of the mapping, in bytes.
+  size: usize,
+  /// The shared memory object.
+  file: File,
+}
+
+// SAFETY: The mapping is not tied to a thread. All concurrent accesses
+//         are coordinated through the atomic counters it contains.
+unsafe impl Send for Mapping {}
+// SAFETY: See above.
+unsafe impl Sync for Mapping {}
+
+impl Mapping {
+  /// Map `size` bytes of the provided shared memory object.
+  fn new(file: File, size: usize) -> io::Result<Self> {
+    // SAFETY: We request a new shared mapping of a valid file
+    //         descriptor.
+    let addr = unsafe {
+      libc::mmap(
+        ptr::null_mut(),
+        size,
+        libc::PROT_READ | libc::PROT_WRITE,
+        libc::MAP_SHARED,
+        file.as_raw_fd(),
+        0,
+      )
+    };
+    if addr == libc::MAP_FAILED {
+      return Err(io::Error::last_os_error())
+    }
+
+    // SAFETY: `mmap` never hands out a null pointer on success.
+    let ptr = unsafe { NonNull::new_unchecked(addr.cast()) };
+    Ok(Self { ptr, size, file })
+  }
+
+  /// Read a header value at the provided offset.
+  ///
+  /// # Safety
+  /// The value must not be modified concurrently.
+  #[inline]
+  unsafe fn read<U>(&self, offset: usize) -> U
+  where
+    U: Copy,
+  {
+    // SAFETY: The header is part of the mapping and the caller
+    //         guarantees the absence of concurrent modifications.
+    unsafe { self.ptr.as_ptr().add(offset).cast::<U>().read_unaligned() }
+  }
+
+  /// Write a header value at the provided offset.
+  ///
+  /// # Safety
+  /// The value must not be accessed concurrently.
+  #[inline]
+  unsafe fn write<U>(&self, offset: usize, value: U) {
+    // SAFETY: The header is part of the mapping and the caller
+    //         guarantees the absence of concurrent accesses.
+    unsafe {
+      self
+        .ptr
+        .as_ptr()
+        .add(offset)
+        .cast::<U>()
+        .write_unaligned(value)
+    }
+  }
+
+  /// Retrieve the layout version.
+  #[inline]
+  fn version(&self) -> &AtomicU32 {
+    // SAFETY: The offset is suitably aligned, as the mapping is page
+    //         aligned, and part of the header.
+    unsafe { &*self.ptr.as_ptr().add(VERSION_OFFSET).cast::<AtomicU32>() }
+  }
+
+  /// Retrieve the head counter.
+  #[inline]
+  fn head(&self) -> &AtomicU64 {
+    // SAFETY: The offset is suitably aligned, as the mapping is page
+    //         aligned, and part of the header.
+    unsafe { &*self.ptr.as_ptr().add(HEAD_OFFSET).cast::<AtomicU64>() }
+  }
+
+  /// Retrieve the tail counter.
+  #[inline]
+  fn tail(&self) -> &AtomicU64 {
+    // SAFETY: The offset is suitably aligned, as the mapping is page
+    //         aligned, and part of the header.
+    unsafe { &*self.ptr.as_ptr().add(TAIL_OFFSET).cast::<AtomicU64>() }
+  }
+
+  /// Retrieve a pointer to the element storage.
+  #[inline]
+  fn data<T>(&self) -> *mut T {
+    // SAFETY: The element storage follows the header and is suitably
+    //         aligned, as checked during construction.
+    unsafe { self.ptr.as_ptr().add(HEADER_LEN).cast() }
+  }
+}
+
+impl Drop for Mapping {
+  fn drop(&mut self) {
+    // SAFETY: We own the mapping.
+    let _rc = unsafe { libc::munmap(self.ptr.as_ptr().cast(), self.size) };
+  }
+}
+
+
+/// The producing half of a shared memory ring buffer.
+///
+/// The producer owns the shared memory object's name and removes it
+/// when dropped. Consumers attached at that point keep working on the
+/// existing ring buffer.
+pub struct Producer<T> {
+  /// The mapped shared memory object.
+  map: Mapping,
+  /// The name of the shared memory object.
+  name: CString,
+  /// The ring buffer's capacity.
+  cap: usize,
+  /// A local copy of the shared head counter, which only we modify.
+  head: u64,
+  /// Phantom data for our element type.
+  _phantom: PhantomData<T>,
+}
+
+impl<T> Producer<T>
+where
+  T: Pod,
+{
+  /// Create a shared memory ring buffer with capacity for `cap`
+  /// elements, named `name`.
+  ///
+  /// `name` has to follow the conventions of `shm_open`, i.e., start
+  /// with a slash and not contain any further ones. A shared memory
+  /// object of the same name, for example one left behind by a
+  /// producer that terminated abnormally, is replaced. If it is still
+  /// in use by a live producer or got replaced by one concurrently, an
+  /// error of kind [`ErrorKind::AddrInUse`] is reported instead.
+  ///
+  /// # Panics
+  /// This constructor panics if `cap` is zero, `T` is zero-sized, or
+  /// `T` has an alignment exceeding 64 bytes.
+  pub fn create(name: &str, cap: usize) -> io::Result<Self> {
+    assert_ne!(cap, 0);
+    let () = check_type::<T>();
+
+    let elem_size =
+      u32::try_from(size_of::<T>()).map_err(|_err| io::Error::from(ErrorKind::OutOfMemory))?;
+    let size = object_size::<T>(cap)?;
+    let name = c_name(name)?;
+
+    let () = Self::remove_stale(&name)?;
+    // SAFETY: The name is a valid NUL terminated string.
+    let fd = check(unsafe {
+      libc::shm_open(
+        name.as_ptr(),
+        libc::O_RDWR | libc::O_CREAT | libc::O_EXCL | libc::O_CLOEXEC,
+        0o600,
+      )
+    })?;
+    // SAFETY: We just opened the file descriptor and exclusively own
+    //         it.
+    let file = unsafe { File::from_raw_fd(fd) };
+    // Should locking fail, the name may already refer to a different
+    // producer's object, which we must not remove.
+    let () = Self::lock(&file, &name)?;
+
+    let result = Self::init(file, size, elem_size, cap);
+    if result.is_err() {
+      // SAFETY: The name is a valid NUL terminated string.
+      let _rc = unsafe { libc::shm_unlink(name.as_ptr()) };
+    }
+
+    let slf = Self {
+      map: result?,
+      name,
+      cap,
+      head: 0,
+      _phantom: PhantomData,
+    };
+    Ok(slf)
+  }
+
+  /// Remove the shared memory object named `name`, if it exists and is
+  /// not locked by a live producer.
+  ///
+  /// Consumers merely take a shared lock on the object temporarily,
+  /// while a live producer holds an exclusive one.
+  fn remove_stale(name: &CStr) -> io::Result<()> {
+    // SAFETY: The name is a valid NUL terminated string.
+    let result = check(unsafe { libc::shm_open(name.as_ptr(), libc::O_RDWR | libc::O_CLOEXEC, 0) });
+    let fd = match result {
+      Ok(fd) => fd,
+      Err(err) if err.kind() == ErrorKind::NotFound => return Ok(()),
+      Err(err) => return Err(err),
+    };
+    // SAFETY: We just opened the file descriptor and exclusively own
+    //         it.
+    let file = unsafe { File::from_raw_fd(fd) };
+
+    // SAFETY: The file descriptor is valid.
+    let result = check(unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) });
+    match result {
+      Ok(_rc) => (),
+      Err(err) if err.kind() == ErrorKind::WouldBlock => {
+        // A consumer checking for the producer's liveness may hold a
+        // shared lock, which we can share unless a producer is around.
+        // SAFETY: The file descriptor is valid.
+        match check(unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_SH | libc::LOCK_NB) }) {
+          Ok(_rc) => (),
+          Err(err) if err.kind() == ErrorKind::WouldBlock => return Err(in_use()),
+          Err(err) => return Err(err),
+        }
+      },
+      Err(err) => return Err(err),
+    }
+
+    // SAFETY: The name is a valid NUL terminated string. Consumers
+    //         still attached to the object are unaffected.
+    let _rc = unsafe { libc::shm_unlink(name.as_ptr()) };
+    Ok(())
+  }
+
+  /// Lock the freshly created shared memory object `file` and make sure
+  /// that it is still the one named `name`.
+  fn lock(file: &File, name: &CStr) -> io::Result<()> {
+    // We hold an exclusive lock for as long as we are alive. The kernel
+    // releases it once our process terminates, even if abnormally,
+    // which allows consumers to detect our absence. Others only ever
+    // lock the object briefly, so we can afford to block.
+    // SAFETY: The file descriptor is valid.
+    let _rc = check(unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) })?;
+
+    // Before we got to lock it, another producer may have considered
+    // the object stale and removed it, possibly replacing it with its
+    // own.
+    // SAFETY: The name is a valid NUL terminated string.
+    let result = check(unsafe { libc::shm_open(name.as_ptr(), libc::O_RDONLY | libc::O_CLOEXEC, 0) });
+    let fd = match result {
+      Ok(fd) => fd,
+      Err(err) if err.kind() == ErrorKind::NotFound => return Err(in_use()),
+      Err(err) => return Err(err),
+    };
+    // SAFETY: We just opened the file descriptor and exclusively own
+    //         it.
+    let named = unsafe { File::from_raw_fd(fd) };
+
+    let ours = file.metadata()?;
+    let named = named.metadata()?;
+    if (ours.dev(), ours.ino()) != (named.dev(), named.ino()) {
+      return Err(in_use())
+    }
+    Ok(())
+  }
+
+  /// Size and map a freshly created and locked shared memory object and
+  /// initialize its header.
+  fn init(file: File, size: usize, elem_size: u32, cap: usize) -> io::Result<Mapping> {
+    let () = file.set_len(size as u64)?;
+
+    let map = Mapping::new(file, size)?;
+    // SAFETY: Consumers only access the header once the version is
+    //         set, which happens last.
+    let () = unsafe { map.write(MAGIC_OFFSET, MAGIC) };
+    // SAFETY: See above.
+    let () = unsafe { map.write(ELEM_SIZE_OFFSET, elem_size) };
+    // SAFETY: See above.
+    let () = unsafe { map.write(CAPACITY_OFFSET, cap as u64) };
+    let () = map.version().store(VERSION, Ordering::Release);
+    Ok(map)
+  }
+
+  /// Push an element into the ring buffer.
+  ///
+  /// If the ring buffer is full, the element is handed back as `Err`.
+  pub fn push(&mut self, elem: T) -> Result<(), T> {
+    if self.push_slice(&[elem]) == 0 {
+      Err(elem)
+    } else {
+      Ok(())
+    }
+  }
+
+  /// Push as many elements from `elems` as fit into the free slots of
+  /// the ring buffer, publishing them in one go.
+  ///
+  /// The number of elements pushed is returned.
+  pub fn push_slice(&mut self, elems: &[T]) -> usize {
+    let tail = self.map.tail().load(Ordering::Acquire);
+    let len = usize::try_from(self.head.wrapping_sub(tail)).unwrap_or(usize::MAX);
+    let count = self.cap.saturating_sub(len).min(elems.len());
+
+    let data = self.map.data::<T>();
+    for (i, elem) in elems[..count].iter().enumerate() {
+      let idx = (self.head.wrapping_add(i as u64) % self.cap as u64) as usize;
+      // SAFETY: The index is in bounds and the slot is free, making it
+      //         accessible only by us.
+      let () = unsafe { data.add(idx).write(*elem) };
+    }
+
+    self.head = self.head.wrapping_add(count as u64);
+    let () = self.map.head().store(self.head, Ordering::Release);
+    count
+  }
+
+  /// Retrieve the ring buffer's capacity.
+  #[inline]
+  pub fn capacity(&self) -> usize {
+    self.cap
+  }
+
+  /// Retrieve the number of elements in the ring buffer.
+  ///
+  /// Because the consumer operates concurrently, the value is only a
+  /// snapshot.
+  #[inline]
+  pub fn len(&self) -> usize {
+    let tail = self.map.tail().load(Ordering::Acquire);
+    let len = self.head.wrapping_sub(tail);
+    usize::try_from(len).unwrap_or(usize::MAX).min(self.cap)
+  }
+
+  /// Check whether the ring buffer is empty.
+  ///
+  /// Because the consumer operates concurrently, the value is only a
+  /// snapshot.
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+}
+
+impl<T> Drop for Producer<T> {
+  fn drop(&mut self) {
+    // SAFETY: The name is a valid NUL terminated string.
+    let _rc = unsafe { libc::shm_unlink(self.name.as_ptr()) };
+  }
+}
+
+impl<T> Debug for Producer<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.debug_struct("Producer")
+      .field("name", &self.name)
+      .field("capacity", &self.cap)
+      .finish()
+  }
+}
+
+
+/// The consuming half of a shared memory ring buffer.
+pub struct Consumer<T> {
+  /// The mapped shared memory object.
+  map: Mapping,
+  /// The ring buffer's capacity.
+  cap: usize,
+  /// A local copy of the shared tail counter, which only we modify.
+  tail: u64,
+  /// Phantom data for our element type.
+  _phantom: PhantomData<T>,
+}
+
+impl<T> Consumer<T>
+where
+  T: Pod,
+{
+  /// Attach to the shared memory ring buffer named `name`, as created
+  /// by a [`Producer`].
+  ///
+  /// An error of kind [`ErrorKind::NotFound`] is reported if no such
+  /// ring buffer exists. [`ErrorKind::WouldBlock`] indicates that the
+  /// producer is still in the process of creating it, in which case
+  /// attaching should be retried. If the shared memory object is not a
+  /// ring buffer of the expected layout version or element size, an
+  /// error of kind [`ErrorKind::InvalidData`] is reported.
+  ///
+  /// Only a single consumer should be attached at any given time.
+  ///
+  /// # Panics
+  /// This constructor panics if `T` is zero-sized or has an alignment
+  /// exceeding 64 bytes.
+  pub fn attach(name: &str) -> io::Result<Self> {
+    let () = check_type::<T>();
+
+    let name = c_name(name)?;
+    // SAFETY: The name is a valid NUL terminated string.
+    let fd = check(unsafe { libc::shm_open(name.as_ptr(), libc::O_RDWR | libc::O_CLOEXEC, 0) })?;
+    // SAFETY: We just opened the file descriptor and exclusively own
+    //         it.
+    let file = unsafe { File::from_raw_fd(fd) };
+
+    let size = usize::try_from(file.metadata()?.len())
+      .map_err(|_err| io::Error::from(ErrorKind::OutOfMemory))?;
+    if size == 0 {
+      return Err(not_ready())
+    }
+    if size < HEADER_LEN {
+      return Err(invalid_data("shared memory object is not a ring buffer"))
+    }
+
+    let map = Mapping::new(file, size)?;
+    let version = map.version().load(Ordering::Acquire);
+    if version == 0 {
+      return Err(not_ready())
+    }
+    // SAFETY: The header is no longer modified once the version is set.
+    if unsafe { map.read::<[u8; 8]>(MAGIC_OFFSET) } != MAGIC {
+      return Err(invalid_data("shared memory object is not a ring buffer"))
+    }
+    if version != VERSION {
+      return Err(invalid_data("ring buffer has unsupported version"))
+    }
+    // SAFETY: See above.
+    if u64::from(unsafe { map.read::<u32>(ELEM_SIZE_OFFSET) }) != size_of::<T>() as u64 {
+      return Err(invalid_data("ring buffer has mismatching element size"))
+    }
+    // SAFETY: See above.
+    let cap = unsafe { map.read::<u64>(CAPACITY_OFFSET) };
+    let cap = usize::try_from(cap)
+      .ok()
+      .filter(|cap| *cap != 0 && object_size::<T>(*cap).ok() == Some(size))
+      .ok_or_else(|| invalid_data("ring buffer has invalid capacity"))?;
+
+    let tail = map.tail().load(Ordering::Acquire);
+    let slf = Self {
+      map,
+      cap,
+      tail,
+      _phantom: PhantomData,
+    };
+    Ok(slf)
+  }
+
+  /// Pop the oldest element from the ring buffer.
+  #[inline]
+  pub fn pop(&mut self) -> Option<T> {
+    let mut elem = [T::zeroed()];
+    if self.pop_slice(&mut elem) == 0 {
+      None
+    } else {
+      Some(elem[0])
+    }
+  }
+
+  /// Pop as many of the oldest elements as available and fit into
+  /// `elems`, releasing their slots in one go.
+  ///
+  /// The number of elements popped is returned.
+  pub fn pop_slice(&mut self, elems: &mut [T]) -> usize {
+    let head = self.map.head().load(Ordering::Acquire);
+    let len = usize::try_from(head.wrapping_sub(self.tail)).unwrap_or(usize::MAX);
+    let count = len.min(self.cap).min(elems.len());
+
+    let data = self.map.data::<T>();
+    for (i, elem) in elems[..count].iter_mut().enumerate() {
+      let idx = (self.tail.wrapping_add(i as u64) % self.cap as u64) as usize;
+      // SAFETY: The index is in bounds and the slot has been published
+      //         by the producer, which won't touch it until we release
+      //         it. Any bit pattern is a valid `T`.
+      *elem = unsafe { data.add(idx).read() };
+    }
+
+    self.tail = self.tail.wrapping_add(count as u64);
+    let () = self.map.tail().store(self.tail, Ordering::Release);
+    count
+  }
+
+  /// Check whether the producer is still alive.
+  ///
+  /// Once the producer got dropped or its process terminated, this
+  /// method returns `false`. Elements pushed before that may still be
+  /// available, so consumers should continue popping until the ring
+  /// buffer is empty.
+  pub fn is_producer_alive(&self) -> bool {
+    let fd = self.map.file.as_raw_fd();
+    // SAFETY: The file descriptor is valid.
+    let rc = unsafe { libc::flock(fd, libc::LOCK_SH | libc::LOCK_NB) };
+    if rc == 0 {
+      // SAFETY: The file descriptor is valid.
+      let _rc = unsafe { libc::flock(fd, libc::LOCK_UN) };
+      false
+    } else {
+      true
+    }
+  }
+
+  /// Retrieve the ring buffer's capacity.
+  #[inline]
+  pub fn capacity(&self) -> usize {
+    self.cap
+  }
+
+  /// Retrieve the number of elements in the ring buffer.
+  ///
+  /// Because the producer operates concurrently, the value is only a
+  /// snapshot.
+  #[inline]
+  pub fn len(&self) -> usize {
+    let head = self.map.head().load(Ordering::Acquire);
+    let len = head.wrapping_sub(self.tail);
+    usize::try_from(len).unwrap_or(usize::MAX).min(self.cap)
+  }
+
+  /// Check whether the ring buffer is empty.
+  ///
+  /// Because the producer operates concurrently, the value is only a
+  /// snapshot.
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+}
+
+impl<T> Debug for Consumer<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.debug_struct("Consumer")
+      .field("capacity", &self.cap)
+      .finish()
+  }
+}
+
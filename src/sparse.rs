@@ -0,0 +1,468 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::mem::replace;
+use core::mem::MaybeUninit;
+use core::ops::Range;
+use core::ptr;
+
+use crate::bits::Bits;
+use crate::util::split_range;
+
+
+/// A cursor for iterating over the occupied slots of a
+/// [`SparseRingBuf`] in logical order.
+#[derive(Clone, Copy, Debug)]
+struct Cursor {
+  /// The number of slots in the ring buffer.
+  len: usize,
+  /// The storage index of the front slot.
+  front: usize,
+  /// The logical index of the next slot to consider in forward
+  /// direction.
+  next: usize,
+  /// The logical index one past the next slot to consider in backward
+  /// direction.
+  next_back: usize,
+}
+
+impl Cursor {
+  /// Split the logical range of slots that remain to be visited into
+  /// (at most) two ranges of storage indexes.
+  #[inline]
+  fn physical_ranges(&self) -> (Range<usize>, Range<usize>) {
+    split_range(self.len, self.front, self.next..self.next_back)
+  }
+
+  /// Map a storage index to a logical one.
+  #[inline]
+  fn logical_idx(&self, idx: usize) -> usize {
+    if idx >= self.front {
+      idx - self.front
+    } else {
+      idx + self.len - self.front
+    }
+  }
+
+  /// Find the next occupied slot, returning its logical and storage
+  /// index.
+  fn next(&mut self, occupied: &Bits) -> Option<(usize, usize)> {
+    let (first, second) = self.physical_ranges();
+    let found = occupied
+      .first_one(first)
+      .or_else(|| occupied.first_one(second));
+
+    if let Some(idx) = found {
+      let logical = self.logical_idx(idx);
+      self.next = logical + 1;
+      Some((logical, idx))
+    } else {
+      self.next = self.next_back;
+      None
+    }
+  }
+
+  /// Find the next occupied slot in backward direction, returning its
+  /// logical and storage index.
+  fn next_back(&mut self, occupied: &Bits) -> Option<(usize, usize)> {
+    let (first, second) = self.physical_ranges();
+    let found = occupied
+      .last_one(second)
+      .or_else(|| occupied.last_one(first));
+
+    if let Some(idx) = found {
+      let logical = self.logical_idx(idx);
+      self.next_back = logical;
+      Some((logical, idx))
+    } else {
+      self.next_back = self.next;
+      None
+    }
+  }
+
+  /// Retrieve the number of slots that remain to be visited.
+  #[inline]
+  fn remaining(&self) -> usize {
+    self.next_back - self.next
+  }
+}
+
+
+macro_rules! iterator {
+  (
+    $(#[$meta:meta])* struct $name:ident,
+    {$( $ref_mut:tt )?},
+    {$ptr_mut:tt},
+  ) => {
+    $(#[$meta])*
+    pub struct $name<'b, T> {
+      /// A pointer to the first slot of the ring buffer's storage.
+      data: *$ptr_mut MaybeUninit<T>,
+      /// The ring buffer's occupancy bitmap.
+      occupied: &'b Bits,
+      /// Our iteration state.
+      cursor: Cursor,
+      /// Phantom data for our lifetime.
+      _phantom: PhantomData<&'b $( $ref_mut )? T>,
+    }
+
+    impl<'b, T> Iterator for $name<'b, T> {
+      type Item = (usize, &'b $( $ref_mut )? T);
+
+      #[inline]
+      fn next(&mut self) -> Option<Self::Item> {
+        let (logical, idx) = self.cursor.next(self.occupied)?;
+        // SAFETY: The index is within bounds, the slot is occupied and,
+        //         given that the cursor only moves forward, we never
+        //         yield a reference to the same slot twice.
+        let elem = unsafe { &$( $ref_mut )?*self.data.add(idx).cast::<T>() };
+        Some((logical, elem))
+      }
+
+      #[inline]
+      fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.cursor.remaining()))
+      }
+    }
+
+    impl<'b, T> DoubleEndedIterator for $name<'b, T> {
+      #[inline]
+      fn next_back(&mut self) -> Option<Self::Item> {
+        let (logical, idx) = self.cursor.next_back(self.occupied)?;
+        // SAFETY: The index is within bounds, the slot is occupied and,
+        //         given that the cursor only moves backward, we never
+        //         yield a reference to the same slot twice.
+        let elem = unsafe { &$( $ref_mut )?*self.data.add(idx).cast::<T>() };
+        Some((logical, elem))
+      }
+    }
+
+    impl<'b, T> FusedIterator for $name<'b, T> {}
+  };
+}
+
+iterator! {
+  /// An iterator over the occupied slots of a [`SparseRingBuf`], along
+  /// with their logical indexes.
+  ///
+  /// Iteration happens front-to-back, unless reversed.
+  #[derive(Clone, Debug)]
+  struct SparseIter, {}, {const},
+}
+iterator! {
+  /// A mutable iterator over the occupied slots of a [`SparseRingBuf`],
+  /// along with their logical indexes.
+  ///
+  /// Iteration happens front-to-back, unless reversed.
+  #[derive(Debug)]
+  struct SparseIterMut, {mut}, {mut},
+}
+
+
+/// A ring buffer with slots that may or may not be occupied.
+///
+/// A `SparseRingBuf<T>` is semantically equivalent to a
+/// `RingBuf<Option<T>>` that has been initialized with `None`. However,
+/// instead of paying for an `Option` discriminant for every slot (which
+/// could be as much as the size of `T` itself, depending on alignment),
+/// it keeps track of which slots are occupied by means of a bitmap,
+/// using a single bit per slot.
+///
+/// Indexing works the same as for [`RingBuf`][crate::RingBuf]: index
+/// `0` refers to the front slot, `self.len() - 1` to the back one, and
+/// indexes wrap around at the ring buffer's end.
+pub struct SparseRingBuf<T> {
+  /// Our actual data. Slots are initialized if and only if their bit in
+  /// `occupied` is set.
+  data: Box<[MaybeUninit<T>]>,
+  /// The occupancy bitmap, indexed by storage index.
+  occupied: Bits,
+  /// The index of the front slot.
+  front: usize,
+}
+
+#[allow(clippy::len_without_is_empty)]
+impl<T> SparseRingBuf<T> {
+  /// Create a new `SparseRingBuf` with `len` unoccupied slots.
+  ///
+  /// # Panics
+  /// This constructor panics if `len` is zero.
+  pub fn new(len: usize) -> Self {
+    assert_ne!(len, 0);
+
+    let mut vec = Vec::with_capacity(len);
+    vec.resize_with(len, MaybeUninit::uninit);
+
+    Self {
+      data: vec.into_boxed_slice(),
+      occupied: Bits::new(len),
+      front: 0,
+    }
+  }
+
+  /// Retrieve the ring buffer's length, i.e., the number of slots it
+  /// contains, occupied or not.
+  #[inline]
+  pub fn len(&self) -> usize {
+    self.data.len()
+  }
+
+  /// Count the number of occupied slots.
+  #[inline]
+  pub fn count(&self) -> usize {
+    self.occupied.count_ones(0..self.len())
+  }
+
+  /// Map a logical index to an index into our storage.
+  #[inline]
+  fn physical_idx(&self, idx: usize) -> usize {
+    (self.front + idx) % self.len()
+  }
+
+  /// Retrieve the storage index of the back slot.
+  #[inline]
+  fn back_idx(&self) -> usize {
+    self.front.checked_sub(1).unwrap_or(self.len() - 1)
+  }
+
+  /// Read the slot at the provided storage index.
+  #[inline]
+  fn slot(&self, idx: usize) -> Option<&T> {
+    if self.occupied.get(idx) {
+      // SAFETY: The slot is occupied and, hence, initialized.
+      Some(unsafe { &*self.data[idx].as_ptr() })
+    } else {
+      None
+    }
+  }
+
+  /// Read the slot at the provided storage index.
+  #[inline]
+  fn slot_mut(&mut self, idx: usize) -> Option<&mut T> {
+    if self.occupied.get(idx) {
+      // SAFETY: The slot is occupied and, hence, initialized.
+      Some(unsafe { &mut *self.data[idx].as_mut_ptr() })
+    } else {
+      None
+    }
+  }
+
+  /// Replace the slot at the provided storage index, returning its
+  /// previous content.
+  fn replace_slot(&mut self, idx: usize, elem: Option<T>) -> Option<T> {
+    let occupied = self.occupied.set(idx, elem.is_some());
+    let slot = &mut self.data[idx];
+    let prev = if let Some(elem) = elem {
+      replace(slot, MaybeUninit::new(elem))
+    } else {
+      replace(slot, MaybeUninit::uninit())
+    };
+
+    if occupied {
+      // SAFETY: The slot was occupied and, hence, initialized.
+      Some(unsafe { ptr::read(prev.as_ptr()) })
+    } else {
+      None
+    }
+  }
+
+  /// Retrieve a reference to the element in the slot at the provided
+  /// logical index, if it is occupied.
+  #[inline]
+  pub fn get(&self, idx: usize) -> Option<&T> {
+    self.slot(self.physical_idx(idx))
+  }
+
+  /// Retrieve a mutable reference to the element in the slot at the
+  /// provided logical index, if it is occupied.
+  #[inline]
+  pub fn get_mut(&mut self, idx: usize) -> Option<&mut T> {
+    self.slot_mut(self.physical_idx(idx))
+  }
+
+  /// Replace the content of the slot at the provided logical index,
+  /// returning its previous content.
+  #[inline]
+  pub fn replace(&mut self, idx: usize, elem: Option<T>) -> Option<T> {
+    self.replace_slot(self.physical_idx(idx), elem)
+  }
+
+  /// Take the element out of the slot at the provided logical index,
+  /// leaving it unoccupied.
+  #[inline]
+  pub fn take(&mut self, idx: usize) -> Option<T> {
+    self.replace(idx, None)
+  }
+
+  /// Retrieve the element in the front slot, if it is occupied.
+  #[inline]
+  pub fn front(&self) -> Option<&T> {
+    self.slot(self.front)
+  }
+
+  /// Retrieve the element in the front slot, if it is occupied.
+  #[inline]
+  pub fn front_mut(&mut self) -> Option<&mut T> {
+    self.slot_mut(self.front)
+  }
+
+  /// Retrieve the element in the back slot, if it is occupied.
+  #[inline]
+  pub fn back(&self) -> Option<&T> {
+    self.slot(self.back_idx())
+  }
+
+  /// Retrieve the element in the back slot, if it is occupied.
+  #[inline]
+  pub fn back_mut(&mut self) -> Option<&mut T> {
+    self.slot_mut(self.back_idx())
+  }
+
+  /// Push a slot to the front of the ring buffer.
+  ///
+  /// This operation will push a new slot before the current front
+  /// into the ring buffer and make it the new front. The back slot gets
+  /// replaced in the process.
+  #[inline]
+  pub fn push_front(&mut self, elem: Option<T>) {
+    let idx = self.back_idx();
+    let _prev = self.replace_slot(idx, elem);
+    self.front = idx;
+  }
+
+  /// Push a slot to the back of the ring buffer.
+  ///
+  /// This operation will push a new slot after the current back into
+  /// the ring buffer and make it the new back. The front slot gets
+  /// replaced in the process.
+  #[inline]
+  pub fn push_back(&mut self, elem: Option<T>) {
+    let idx = self.front;
+    let _prev = self.replace_slot(idx, elem);
+    self.front = (idx + 1) % self.len();
+  }
+
+  /// Pop the front slot from the ring buffer.
+  ///
+  /// This operation will take the content out of the front slot,
+  /// leaving it unoccupied. The slot after the current front will
+  /// become the new front.
+  #[inline]
+  pub fn pop_front(&mut self) -> Option<T> {
+    let idx = self.front;
+    self.front = (idx + 1) % self.len();
+    self.replace_slot(idx, None)
+  }
+
+  /// Pop the back slot from the ring buffer.
+  ///
+  /// This operation will take the content out of the back slot, leaving
+  /// it unoccupied. The slot before the current back will become the
+  /// new back.
+  #[inline]
+  pub fn pop_back(&mut self) -> Option<T> {
+    let idx = self.back_idx();
+    self.front = idx;
+    self.replace_slot(idx, None)
+  }
+
+  /// Take the elements out of all slots, leaving them unoccupied.
+  pub fn clear(&mut self) {
+    for idx in 0..self.len() {
+      let _prev = self.replace_slot(idx, None);
+    }
+  }
+
+  /// Create a cursor covering all slots.
+  #[inline]
+  fn cursor(&self) -> Cursor {
+    Cursor {
+      len: self.len(),
+      front: self.front,
+      next: 0,
+      next_back: self.len(),
+    }
+  }
+
+  /// Retrieve an iterator over the occupied slots of the ring buffer,
+  /// along with their logical indexes.
+  ///
+  /// The iterator traverses the ring buffer in front-to-back manner,
+  /// skipping over unoccupied slots.
+  #[inline]
+  pub fn iter(&self) -> SparseIter<'_, T> {
+    SparseIter {
+      data: self.data.as_ptr(),
+      occupied: &self.occupied,
+      cursor: self.cursor(),
+      _phantom: PhantomData,
+    }
+  }
+
+  /// Retrieve a mutable iterator over the occupied slots of the ring
+  /// buffer, along with their logical indexes.
+  ///
+  /// The iterator traverses the ring buffer in front-to-back manner,
+  /// skipping over unoccupied slots.
+  #[inline]
+  pub fn iter_mut(&mut self) -> SparseIterMut<'_, T> {
+    let cursor = self.cursor();
+    SparseIterMut {
+      data: self.data.as_mut_ptr(),
+      occupied: &self.occupied,
+      cursor,
+      _phantom: PhantomData,
+    }
+  }
+}
+
+impl<T> Clone for SparseRingBuf<T>
+where
+  T: Clone,
+{
+  fn clone(&self) -> Self {
+    let mut other = Self::new(self.len());
+    other.front = self.front;
+    for idx in 0..self.len() {
+      let _prev = other.replace_slot(idx, self.slot(idx).cloned());
+    }
+    other
+  }
+}
+
+impl<T> Debug for SparseRingBuf<T>
+where
+  T: Debug,
+{
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.debug_list()
+      .entries((0..self.len()).map(|idx| self.get(idx)))
+      .finish()
+  }
+}
+
+impl<T> Drop for SparseRingBuf<T> {
+  fn drop(&mut self) {
+    let () = self.clear();
+  }
+}
+
+/// Two `SparseRingBuf` objects are considered equal if they have the
+/// same length and their slots at each logical index compare equal.
+impl<T> PartialEq for SparseRingBuf<T>
+where
+  T: PartialEq,
+{
+  fn eq(&self, other: &Self) -> bool {
+    self.len() == other.len() && (0..self.len()).all(|idx| self.get(idx) == other.get(idx))
+  }
+}
+
+impl<T> Eq for SparseRingBuf<T> where T: Eq {}
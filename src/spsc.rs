@@ -0,0 +1,465 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+//! A lock-free single-producer/single-consumer ring buffer.
+//!
+//! A ring buffer is created by means of [`channel`], which returns a
+//! [`Producer`] and a [`Consumer`] half. Each half can be moved to a
+//! different thread, allowing for the exchange of elements between the
+//! two without any locking.
+//!
+//! # Examples
+//! ```rust
+//! # use std::thread;
+//! use rbuf::spsc::channel;
+//! use rbuf::spsc::Overflow;
+//!
+//! let (mut producer, mut consumer) = channel(4, Overflow::Reject);
+//! let thread = thread::spawn(move || {
+//!   for i in 0..16 {
+//!     while producer.push(i).is_err() {
+//!       thread::yield_now();
+//!     }
+//!   }
+//! });
+//!
+//! let mut next = 0;
+//! while next < 16 {
+//!   if let Some(i) = consumer.pop() {
+//!     assert_eq!(i, next);
+//!     next += 1;
+//!   }
+//! }
+//! let () = thread.join().unwrap();
+//! ```
+
+use alloc::boxed::Box;
+use core::fmt::Debug;
+use core::fmt::Formatter;
+use core::fmt::Result as FmtResult;
+use core::mem::MaybeUninit;
+
+use crate::sync::atomic::AtomicUsize;
+use crate::sync::atomic::Ordering;
+use crate::sync::Arc;
+use crate::sync::CachePadded;
+use crate::sync::UnsafeCell;
+
+
+/// The behavior of a ring buffer when pushing an element to it while it
+/// is full.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Overflow {
+  /// Evict the oldest element to make room for the new one.
+  Overwrite,
+  /// Reject the new element.
+  Reject,
+}
+
+
+/// The state shared between a [`Producer`] and a [`Consumer`].
+///
+/// All counters are free-running and only get reduced to a storage
+/// index when accessing a slot. The elements in `tail..head` are
+/// available for consumption. Elements get claimed by advancing `tail`,
+/// which usually happens on the consumer side, but may also be done by
+/// the producer, when evicting the oldest element. Once the consumer
+/// has moved a claimed element out of its slot, it advances `consumed`
+/// to signal that the slot may be reused.
+struct Shared<T> {
+  /// The counter of the next element to be pushed.
+  head: CachePadded<AtomicUsize>,
+  /// The counter of the next element to be claimed.
+  tail: CachePadded<AtomicUsize>,
+  /// The counter of the first element the consumer has not yet moved
+  /// out of its slot.
+  consumed: CachePadded<AtomicUsize>,
+  /// The behavior when pushing to a full ring buffer.
+  overflow: Overflow,
+  /// Our actual data.
+  data: Box<[UnsafeCell<MaybeUninit<T>>]>,
+}
+
+// SAFETY: Access to each slot is coordinated through our atomic
+//         counters, which hand out exclusive ownership of a slot to
+//         either side. Elements are only ever moved between threads,
+//         never shared.
+unsafe impl<T> Send for Shared<T> where T: Send {}
+// SAFETY: See above.
+unsafe impl<T> Sync for Shared<T> where T: Send {}
+
+impl<T> Shared<T> {
+  /// Retrieve the slot for the element with the provided counter.
+  #[inline]
+  fn slot(&self, idx: usize) -> &UnsafeCell<MaybeUninit<T>> {
+    &self.data[idx % self.data.len()]
+  }
+
+  /// Write an element into the slot for the provided counter.
+  ///
+  /// # Safety
+  /// The caller needs to have exclusive access to the slot.
+  #[inline]
+  unsafe fn write(&self, idx: usize, elem: T) {
+    self.slot(idx).with_mut(|ptr| {
+      // SAFETY: The caller guarantees exclusive access to the slot.
+      let _elem = unsafe { &mut *ptr }.write(elem);
+    })
+  }
+
+  /// Move the element out of the slot for the provided counter.
+  ///
+  /// # Safety
+  /// The caller needs to have exclusive access to the slot and the slot
+  /// has to contain an initialized element.
+  #[inline]
+  unsafe fn read(&self, idx: usize) -> T {
+    self.slot(idx).with(|ptr| {
+      // SAFETY: The caller guarantees exclusive access to the slot and
+      //         that it is initialized.
+      unsafe { ptr.read().assume_init() }
+    })
+  }
+
+  /// Retrieve the number of elements available for consumption.
+  #[inline]
+  fn len(&self) -> usize {
+    let tail = self.tail.load(Ordering::Acquire);
+    let head = self.head.load(Ordering::Acquire);
+    head.wrapping_sub(tail)
+  }
+}
+
+impl<T> Drop for Shared<T> {
+  fn drop(&mut self) {
+    let tail = self.tail.load(Ordering::Relaxed);
+    let head = self.head.load(Ordering::Relaxed);
+
+    let mut idx = tail;
+    while idx != head {
+      // SAFETY: We have exclusive access to all slots and those in
+      //         `tail..head` are initialized.
+      let _elem = unsafe { self.read(idx) };
+      idx = idx.wrapping_add(1);
+    }
+  }
+}
+
+
+/// Create a new lock-free single-producer/single-consumer ring buffer
+/// with capacity for `cap` elements.
+///
+/// The `overflow` argument determines what happens when pushing an
+/// element while the ring buffer is full.
+///
+/// # Panics
+/// This function panics if `cap` is zero.
+pub fn channel<T>(cap: usize, overflow: Overflow) -> (Producer<T>, Consumer<T>) {
+  assert_ne!(cap, 0);
+
+  let data = (0..cap)
+    .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+    .collect();
+  let shared = Arc::new(Shared {
+    head: CachePadded(AtomicUsize::new(0)),
+    tail: CachePadded(AtomicUsize::new(0)),
+    consumed: CachePadded(AtomicUsize::new(0)),
+    overflow,
+    data,
+  });
+
+  let producer = Producer {
+    shared: Arc::clone(&shared),
+    head: 0,
+  };
+  let consumer = Consumer { shared };
+  (producer, consumer)
+}
+
+
+/// The producing half of a lock-free single-producer/single-consumer
+/// ring buffer.
+pub struct Producer<T> {
+  /// The state shared with the consumer.
+  shared: Arc<Shared<T>>,
+  /// A local copy of the shared head counter, which only we modify.
+  head: usize,
+}
+
+impl<T> Producer<T> {
+  /// Reserve the slot for the element with counter `self.head`.
+  ///
+  /// On success, the element evicted to make room, if any, is returned.
+  /// `Err` indicates that the ring buffer is full and either configured
+  /// to reject new elements or the consumer is in the process of
+  /// popping the element that would have to be evicted.
+  fn reserve(&mut self) -> Result<Option<T>, ()> {
+    let head = self.head;
+    let cap = self.capacity();
+
+    let consumed = self.shared.consumed.load(Ordering::Acquire);
+    if head.wrapping_sub(consumed) < cap {
+      return Ok(None)
+    }
+
+    if self.shared.overflow == Overflow::Reject {
+      return Err(())
+    }
+
+    let oldest = head.wrapping_sub(cap);
+    let result = self.shared.tail.compare_exchange(
+      oldest,
+      oldest.wrapping_add(1),
+      Ordering::AcqRel,
+      Ordering::Relaxed,
+    );
+
+    if result.is_ok() {
+      // SAFETY: We just claimed the oldest element, which gives us
+      //         exclusive access to its slot.
+      let evicted = unsafe { self.shared.read(oldest) };
+      // The consumer may lag behind for an element we evicted. Bring
+      // its counter up to date, unless it has moved on already.
+      let _result = self.shared.consumed.compare_exchange(
+        consumed,
+        oldest.wrapping_add(1),
+        Ordering::Relaxed,
+        Ordering::Relaxed,
+      );
+      return Ok(Some(evicted))
+    }
+
+    // The consumer claimed the oldest element. If it has moved it out
+    // of its slot by now, the slot is free. Otherwise we do not wait
+    // for it, as it may have been preempted and the wait could be
+    // arbitrarily long.
+    let consumed = self.shared.consumed.load(Ordering::Acquire);
+    if head.wrapping_sub(consumed) < cap {
+      Ok(None)
+    } else {
+      Err(())
+    }
+  }
+
+  /// Push an element into the ring buffer.
+  ///
+  /// If the ring buffer is full and configured to overwrite, the oldest
+  /// element is evicted and returned as `Ok(Some(..))`. If it is
+  /// configured to reject, the provided element is handed back as
+  /// `Err`.
+  ///
+  /// Pushing never waits for the consumer. Hence, if the ring buffer is
+  /// full and the consumer is concurrently popping the oldest element,
+  /// the provided element is handed back as `Err` even when configured
+  /// to overwrite.
+  pub fn push(&mut self, elem: T) -> Result<Option<T>, T> {
+    let evicted = match self.reserve() {
+      Ok(evicted) => evicted,
+      Err(()) => return Err(elem),
+    };
+
+    // SAFETY: We reserved the slot at `self.head`.
+    let () = unsafe { self.shared.write(self.head, elem) };
+    self.head = self.head.wrapping_add(1);
+    let () = self.shared.head.store(self.head, Ordering::Release);
+    Ok(evicted)
+  }
+
+  /// Push as many elements from `elems` as fit into the free slots of
+  /// the ring buffer, publishing them in one go.
+  fn push_free(&mut self, elems: &[T]) -> usize
+  where
+    T: Copy,
+  {
+    let consumed = self.shared.consumed.load(Ordering::Acquire);
+    let free = self.capacity() - self.head.wrapping_sub(consumed);
+    let count = free.min(elems.len());
+
+    for (i, elem) in elems[..count].iter().enumerate() {
+      // SAFETY: All slots up to `consumed + cap` are free and only
+      //         accessible by us.
+      let () = unsafe { self.shared.write(self.head.wrapping_add(i), *elem) };
+    }
+
+    self.head = self.head.wrapping_add(count);
+    let () = self.shared.head.store(self.head, Ordering::Release);
+    count
+  }
+
+  /// Push a slice of elements into the ring buffer.
+  ///
+  /// Compared to pushing elements one by one, the consumer is notified
+  /// about all elements at once. If the ring buffer is configured to
+  /// overwrite, all elements are pushed, potentially evicting older
+  /// ones, unless the consumer is concurrently popping the element to
+  /// evict (see [`Producer::push`]). If it is configured to reject,
+  /// elements are pushed until it is full.
+  ///
+  /// The number of elements pushed is returned.
+  pub fn push_slice(&mut self, elems: &[T]) -> usize
+  where
+    T: Copy,
+  {
+    let mut remaining = elems;
+    if self.shared.overflow == Overflow::Overwrite {
+      // Elements that would be evicted by later ones in the slice need
+      // not be pushed in the first place.
+      remaining = &elems[elems.len().saturating_sub(self.capacity())..];
+    }
+
+    while !remaining.is_empty() {
+      let count = self.push_free(remaining);
+      if count == 0 {
+        match self.push(remaining[0]) {
+          Ok(_evicted) => remaining = &remaining[1..],
+          Err(_elem) => break,
+        }
+      } else {
+        remaining = &remaining[count..];
+      }
+    }
+
+    elems.len() - remaining.len()
+  }
+
+  /// Retrieve the ring buffer's capacity.
+  #[inline]
+  pub fn capacity(&self) -> usize {
+    self.shared.data.len()
+  }
+
+  /// Retrieve the number of elements in the ring buffer.
+  ///
+  /// Because the consumer operates concurrently, the value is only a
+  /// snapshot.
+  #[inline]
+  pub fn len(&self) -> usize {
+    self.shared.len()
+  }
+
+  /// Check whether the ring buffer is empty.
+  ///
+  /// Because the consumer operates concurrently, the value is only a
+  /// snapshot.
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+}
+
+impl<T> Debug for Producer<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.debug_struct("Producer")
+      .field("capacity", &self.capacity())
+      .field("overflow", &self.shared.overflow)
+      .finish()
+  }
+}
+
+
+/// The consuming half of a lock-free single-producer/single-consumer
+/// ring buffer.
+pub struct Consumer<T> {
+  /// The state shared with the producer.
+  shared: Arc<Shared<T>>,
+}
+
+impl<T> Consumer<T> {
+  /// Claim up to `max` elements, returning the counter of the first one
+  /// along with the number claimed.
+  fn claim(&mut self, max: usize) -> (usize, usize) {
+    loop {
+      // Acquire ordering to observe all elements published before the
+      // producer may have evicted the element at `tail`.
+      let tail = self.shared.tail.load(Ordering::Acquire);
+      let head = self.shared.head.load(Ordering::Acquire);
+      let count = head.wrapping_sub(tail).min(max);
+      if count == 0 {
+        break (tail, 0)
+      }
+
+      let result = self.shared.tail.compare_exchange(
+        tail,
+        tail.wrapping_add(count),
+        Ordering::Relaxed,
+        Ordering::Relaxed,
+      );
+      if result.is_ok() {
+        break (tail, count)
+      }
+      // The producer evicted the element at `tail`. Try again.
+    }
+  }
+
+  /// Mark all claimed elements up to `idx` as moved out of their slots.
+  #[inline]
+  fn release(&mut self, idx: usize) {
+    let () = self.shared.consumed.store(idx, Ordering::Release);
+  }
+
+  /// Pop the oldest element from the ring buffer.
+  pub fn pop(&mut self) -> Option<T> {
+    let (tail, count) = self.claim(1);
+    if count == 0 {
+      return None
+    }
+
+    // SAFETY: We claimed the element, which gives us exclusive access to
+    //         its initialized slot.
+    let elem = unsafe { self.shared.read(tail) };
+    let () = self.release(tail.wrapping_add(1));
+    Some(elem)
+  }
+
+  /// Pop elements from the ring buffer into `buf`, oldest first.
+  ///
+  /// Compared to popping elements one by one, all elements are claimed
+  /// at once. The number of elements popped is returned.
+  pub fn pop_slice(&mut self, buf: &mut [T]) -> usize
+  where
+    T: Copy,
+  {
+    let (tail, count) = self.claim(buf.len());
+    for (i, elem) in buf[..count].iter_mut().enumerate() {
+      // SAFETY: We claimed the elements, which gives us exclusive access
+      //         to their initialized slots.
+      *elem = unsafe { self.shared.read(tail.wrapping_add(i)) };
+    }
+
+    let () = self.release(tail.wrapping_add(count));
+    count
+  }
+
+  /// Retrieve the ring buffer's capacity.
+  #[inline]
+  pub fn capacity(&self) -> usize {
+    self.shared.data.len()
+  }
+
+  /// Retrieve the number of elements in the ring buffer.
+  ///
+  /// Because the producer operates concurrently, the value is only a
+  /// snapshot.
+  #[inline]
+  pub fn len(&self) -> usize {
+    self.shared.len()
+  }
+
+  /// Check whether the ring buffer is empty.
+  ///
+  /// Because the producer operates concurrently, the value is only a
+  /// snapshot.
+  #[inline]
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+}
+
+impl<T> Debug for Consumer<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+    f.debug_struct("Consumer")
+      .field("capacity", &self.capacity())
+      .field("overflow", &self.shared.overflow)
+      .finish()
+  }
+}
@@ -0,0 +1,65 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+
+/// A trait for types usable as storage of a [`RingBuf`][crate::RingBuf].
+///
+/// Besides providing access to the data, a storage type decides how the
+/// ring buffer tracks the position of its front element. By default,
+/// that position is an index into storage, wrapped around at its end by
+/// means of the modulo operation. Implementations may instead use a
+/// free-running counter and only map it onto an index when accessing
+/// elements, as [`Pow2`][crate::Pow2] does.
+///
+/// # Safety
+/// The slices provided by the [`AsRef`] and [`AsMut`] implementations
+/// have to always have the same length. The ring buffer caches this
+/// length and relies on it for skipping bounds checks when accessing
+/// elements. For the same reason, [`Storage::index`] has to return a
+/// value less than the length it is provided with for any position
+/// reachable from zero by means of [`Storage::advance`] and
+/// [`Storage::retreat`].
+pub unsafe trait Storage<T>: AsRef<[T]> + AsMut<[T]> {
+  /// Map the position `pos` onto an index into storage of length
+  /// `len`.
+  ///
+  /// The default implementation uses positions as indexes directly.
+  #[inline]
+  fn index(pos: usize, _len: usize) -> usize {
+    pos
+  }
+
+  /// Advance the position `pos` by `n` in storage of length `len`.
+  ///
+  /// The default implementation wraps around at `len` using the modulo
+  /// operation.
+  #[inline]
+  fn advance(pos: usize, n: usize, len: usize) -> usize {
+    (pos + n) % len
+  }
+
+  /// Move the position `pos` back by `n`, which is at most `len`, in
+  /// storage of length `len`.
+  ///
+  /// The default implementation wraps around at zero.
+  #[inline]
+  fn retreat(pos: usize, n: usize, len: usize) -> usize {
+    pos.checked_sub(n).unwrap_or_else(|| pos + len - n)
+  }
+}
+
+// SAFETY: A boxed slice cannot change its length.
+unsafe impl<T> Storage<T> for Box<[T]> {}
+
+// SAFETY: The ring buffer only ever accesses the vector as a slice,
+//         which cannot change its length.
+unsafe impl<T> Storage<T> for Vec<T> {}
+
+// SAFETY: An array has a fixed length.
+unsafe impl<T, const N: usize> Storage<T> for [T; N] {}
+
+// SAFETY: A slice cannot change its length.
+unsafe impl<T> Storage<T> for &mut [T] {}
@@ -0,0 +1,89 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+// Synchronization primitives used by the concurrent ring buffers. When
+// building with `--cfg loom`, they are replaced with their `loom`
+// counterparts, allowing for model checking of said ring buffers.
+
+#[cfg(not(loom))]
+use core::cell::UnsafeCell as CoreUnsafeCell;
+use core::ops::Deref;
+use core::ops::DerefMut;
+
+#[cfg(not(loom))]
+pub(crate) use alloc::sync::Arc;
+#[cfg(not(loom))]
+pub(crate) use core::hint::spin_loop;
+#[cfg(not(loom))]
+pub(crate) use core::sync::atomic;
+
+#[cfg(loom)]
+pub(crate) use loom::cell::UnsafeCell;
+#[cfg(loom)]
+pub(crate) use loom::hint::spin_loop;
+#[cfg(loom)]
+pub(crate) use loom::sync::atomic;
+#[cfg(loom)]
+pub(crate) use loom::sync::Arc;
+
+
+/// A wrapper around `core::cell::UnsafeCell` providing the closure
+/// based interface of `loom`'s `UnsafeCell`.
+#[cfg(not(loom))]
+#[derive(Debug)]
+#[repr(transparent)]
+pub(crate) struct UnsafeCell<T>(CoreUnsafeCell<T>);
+
+#[cfg(not(loom))]
+impl<T> UnsafeCell<T> {
+  /// Create a new `UnsafeCell` wrapping the provided value.
+  #[inline]
+  pub(crate) const fn new(data: T) -> Self {
+    Self(CoreUnsafeCell::new(data))
+  }
+
+  /// Invoke `f` with an immutable pointer to the wrapped value.
+  #[inline]
+  pub(crate) fn with<F, R>(&self, f: F) -> R
+  where
+    F: FnOnce(*const T) -> R,
+  {
+    f(self.0.get())
+  }
+
+  /// Invoke `f` with a mutable pointer to the wrapped value.
+  #[inline]
+  pub(crate) fn with_mut<F, R>(&self, f: F) -> R
+  where
+    F: FnOnce(*mut T) -> R,
+  {
+    f(self.0.get())
+  }
+}
+
+
+/// A wrapper aligning its contents to a cache line boundary, to prevent
+/// false sharing between values accessed by different threads.
+///
+/// We align to 128 bytes, because some architectures (e.g., `x86_64`
+/// with adjacent line prefetching or Apple's `aarch64` chips) operate
+/// on pairs of 64 byte cache lines.
+#[derive(Debug, Default)]
+#[repr(align(128))]
+pub(crate) struct CachePadded<T>(pub(crate) T);
+
+impl<T> Deref for CachePadded<T> {
+  type Target = T;
+
+  #[inline]
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
+
+impl<T> DerefMut for CachePadded<T> {
+  #[inline]
+  fn deref_mut(&mut self) -> &mut Self::Target {
+    &mut self.0
+  }
+}
@@ -0,0 +1,18 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use std::io;
+use std::os::raw::c_int;
+
+
+/// Check the return value of a `libc` call, converting a failure into
+/// an `io::Error`.
+#[inline]
+pub(crate) fn check(rc: c_int) -> io::Result<c_int> {
+  if rc < 0 {
+    Err(io::Error::last_os_error())
+  } else {
+    Ok(rc)
+  }
+}
+
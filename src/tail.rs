@@ -0,0 +1,189 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+
+use crate::RingBuf;
+
+
+/// A sink retaining only the most recently written bytes.
+///
+/// A `TailWriter` accepts arbitrary amounts of data, but only ever
+/// keeps the last `capacity` bytes written, overwriting older ones.
+/// That makes it suitable for capturing, say, the tail end of a child
+/// process' error output.
+///
+/// `TailWriter` implements [`fmt::Write`][core::fmt::Write] and, when
+/// the `std` feature is enabled, `io::Write`.
+///
+/// # Examples
+/// ```rust
+/// # use core::fmt::Write as _;
+/// # use rbuf::TailWriter;
+/// let mut tail = TailWriter::new(8);
+/// let () = write!(tail, "exit status: {}", 42).unwrap();
+/// assert!(tail.is_truncated());
+/// assert_eq!(tail.to_string_lossy(), "atus: 42");
+/// ```
+#[derive(Clone, Debug)]
+pub struct TailWriter {
+  /// Our actual data. The back of the ring buffer holds the most
+  /// recently written byte.
+  buf: RingBuf<u8>,
+  /// The number of valid bytes, ending at the back of the ring buffer.
+  len: usize,
+  /// Whether any bytes have been overwritten or discarded.
+  truncated: bool,
+}
+
+impl TailWriter {
+  /// Create a new, empty `TailWriter` retaining up to `capacity` bytes.
+  ///
+  /// # Panics
+  /// This constructor panics if `capacity` is zero.
+  pub fn new(capacity: usize) -> Self {
+    Self {
+      buf: RingBuf::new(capacity),
+      len: 0,
+      truncated: false,
+    }
+  }
+
+  /// Retrieve the maximum number of bytes retained.
+  #[inline]
+  pub fn capacity(&self) -> usize {
+    self.buf.len()
+  }
+
+  /// Retrieve the number of bytes currently retained.
+  #[inline]
+  pub const fn len(&self) -> usize {
+    self.len
+  }
+
+  /// Check whether no bytes have been written.
+  #[inline]
+  pub const fn is_empty(&self) -> bool {
+    self.len == 0
+  }
+
+  /// Check whether any written bytes have been discarded in favor of
+  /// more recent ones.
+  #[inline]
+  pub const fn is_truncated(&self) -> bool {
+    self.truncated
+  }
+
+  /// Write `data`, discarding the oldest bytes as necessary.
+  pub fn push_slice(&mut self, data: &[u8]) {
+    let cap = self.capacity();
+    let data = if data.len() > cap {
+      self.truncated = true;
+      &data[data.len() - cap..]
+    } else {
+      data
+    };
+
+    if self.len + data.len() > cap {
+      self.truncated = true;
+    }
+
+    // Overwrite the oldest slots and then rotate them to the back.
+    let (first, second) = self.buf.range_slices_mut(0..data.len());
+    let (data1, data2) = data.split_at(first.len());
+    let () = first.copy_from_slice(data1);
+    let () = second.copy_from_slice(data2);
+    let () = self.buf.rotate_left(data.len());
+
+    self.len = (self.len + data.len()).min(cap);
+  }
+
+  /// Retrieve the retained bytes as a pair of slices.
+  ///
+  /// The first slice starts with the oldest byte, the second one ends
+  /// with the most recently written one. Either of them may be empty.
+  #[inline]
+  pub fn contents(&self) -> (&[u8], &[u8]) {
+    let cap = self.capacity();
+    self.buf.range_slices(cap - self.len..cap)
+  }
+
+  /// Convert the retained bytes into a `String`, replacing invalid
+  /// UTF-8 sequences with `U+FFFD REPLACEMENT CHARACTER`.
+  ///
+  /// If the retained bytes start in the middle of a character whose
+  /// beginning got discarded, the remainder of said character is
+  /// skipped instead of being replaced.
+  pub fn to_string_lossy(&self) -> String {
+    let (first, second) = self.contents();
+    let mut bytes = Vec::with_capacity(self.len);
+    let () = bytes.extend_from_slice(first);
+    let () = bytes.extend_from_slice(second);
+
+    let skip = if self.truncated {
+      // A UTF-8 encoded character has at most three continuation
+      // bytes, which all have the form `0b10xxxxxx`.
+      bytes
+        .iter()
+        .take(3)
+        .take_while(|byte| *byte & 0xc0 == 0x80)
+        .count()
+    } else {
+      0
+    };
+
+    String::from_utf8_lossy(&bytes[skip..]).into_owned()
+  }
+
+  /// Discard all retained bytes.
+  #[inline]
+  pub fn clear(&mut self) {
+    self.len = 0;
+    self.truncated = false;
+  }
+}
+
+/// Two `TailWriter` objects are considered equal if they retain the same
+/// bytes and agree on whether any have been discarded, regardless of
+/// their capacity or stale data in storage.
+impl PartialEq for TailWriter {
+  fn eq(&self, other: &Self) -> bool {
+    let (first, second) = self.contents();
+    let (other_first, other_second) = other.contents();
+
+    self.len == other.len
+      && self.truncated == other.truncated
+      && first
+        .iter()
+        .chain(second)
+        .eq(other_first.iter().chain(other_second))
+  }
+}
+
+impl Eq for TailWriter {}
+
+impl fmt::Write for TailWriter {
+  #[inline]
+  fn write_str(&mut self, s: &str) -> fmt::Result {
+    let () = self.push_slice(s.as_bytes());
+    Ok(())
+  }
+}
+
+#[cfg(feature = "std")]
+impl io::Write for TailWriter {
+  #[inline]
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    let () = self.push_slice(buf);
+    Ok(buf.len())
+  }
+
+  #[inline]
+  fn flush(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+}
@@ -0,0 +1,67 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+use core::ops::Bound;
+use core::ops::Range;
+use core::ops::RangeBounds;
+
+
+/// Convert a generic range into a `Range` of indexes into a sequence of
+/// `len` elements.
+///
+/// # Panics
+/// This function panics if the range is decreasing or extends past
+/// `len`.
+pub(crate) fn to_range<R>(range: R, len: usize) -> Range<usize>
+where
+  R: RangeBounds<usize>,
+{
+  let start = match range.start_bound() {
+    Bound::Included(start) => *start,
+    Bound::Excluded(start) => start
+      .checked_add(1)
+      .expect("range start index overflowed"),
+    Bound::Unbounded => 0,
+  };
+  let end = match range.end_bound() {
+    Bound::Included(end) => end.checked_add(1).expect("range end index overflowed"),
+    Bound::Excluded(end) => *end,
+    Bound::Unbounded => len,
+  };
+
+  assert!(
+    start <= end,
+    "range start index {start} is greater than end index {end}"
+  );
+  assert!(
+    end <= len,
+    "range end index {end} out of range for ring buffer of length {len}"
+  );
+  start..end
+}
+
+/// Split a range of logical indexes into a ring buffer with `cap` slots
+/// and the front at storage index `front` into (at most) two ranges of
+/// storage indexes.
+///
+/// The provided range has to be a subset of `0..cap`.
+#[inline]
+pub(crate) fn split_range(
+  cap: usize,
+  front: usize,
+  range: Range<usize>,
+) -> (Range<usize>, Range<usize>) {
+  debug_assert!(front < cap || cap == 0);
+  debug_assert!(range.start <= range.end && range.end <= cap);
+
+  let start = front + range.start;
+  let end = front + range.end;
+
+  if start >= cap {
+    (start - cap..end - cap, 0..0)
+  } else if end > cap {
+    (start..cap, 0..end - cap)
+  } else {
+    (start..end, 0..0)
+  }
+}
@@ -0,0 +1,167 @@
+// Copyright (C) 2025 Daniel Mueller <deso@posteo.net>
+// SPDX-License-Identifier: (Apache-2.0 OR MIT)
+
+//! Model checking tests for the crate's concurrent ring buffers.
+//!
+//! Run with:
+//! ```sh
+//! RUSTFLAGS='--cfg loom' cargo test --release --test loom
+//! ```
+
+#![cfg(loom)]
+
+use loom::sync::Arc;
+use loom::thread;
+
+use rbuf::spsc;
+use rbuf::spsc::Overflow;
+use rbuf::MpmcRing;
+
+
+/// Check that elements are exchanged in order through an SPSC ring
+/// buffer rejecting elements when full.
+#[test]
+fn spsc_reject() {
+  loom::model(|| {
+    let (mut producer, mut consumer) = spsc::channel(2, Overflow::Reject);
+    let thread = thread::spawn(move || {
+      for i in 0..3 {
+        while producer.push(i).is_err() {
+          let () = thread::yield_now();
+        }
+      }
+    });
+
+    let mut next = 0;
+    while next < 3 {
+      if let Some(i) = consumer.pop() {
+        assert_eq!(i, next);
+        next += 1;
+      } else {
+        let () = thread::yield_now();
+      }
+    }
+    let () = thread.join().unwrap();
+  });
+}
+
+/// Check that no element is lost or duplicated when the producer of an
+/// SPSC ring buffer evicts elements concurrently to them being popped.
+///
+/// Elements that the producer had handed back, because the consumer was
+/// busy popping the one to evict, count as evicted.
+#[test]
+fn spsc_overwrite() {
+  loom::model(|| {
+    let (mut producer, mut consumer) = spsc::channel(1, Overflow::Overwrite);
+    let thread = thread::spawn(move || {
+      let mut evicted = Vec::new();
+      for i in 0..3 {
+        match producer.push(i) {
+          Ok(None) => (),
+          Ok(Some(elem)) | Err(elem) => {
+            let () = evicted.push(elem);
+          },
+        }
+      }
+      evicted
+    });
+
+    let mut popped = Vec::new();
+    for _ in 0..2 {
+      if let Some(i) = consumer.pop() {
+        let () = popped.push(i);
+      }
+    }
+    let evicted = thread.join().unwrap();
+    let remaining = consumer.pop();
+
+    let mut all = popped.clone();
+    let () = all.extend(evicted);
+    let () = all.extend(remaining);
+    let () = all.sort();
+    assert_eq!(all, [0, 1, 2]);
+    assert!(popped.windows(2).all(|w| w[0] < w[1]));
+  });
+}
+
+/// Check that batch operations on an SPSC ring buffer exchange elements
+/// correctly.
+#[test]
+fn spsc_slices() {
+  loom::model(|| {
+    let (mut producer, mut consumer) = spsc::channel(2, Overflow::Overwrite);
+    let thread = thread::spawn(move || {
+      assert_eq!(producer.push_slice(&[1, 2]), 2);
+      // The push may fail if the consumer is in the middle of popping.
+      producer.push_slice(&[3])
+    });
+
+    let mut popped = Vec::new();
+    let mut buf = [0; 2];
+    let count = consumer.pop_slice(&mut buf);
+    let () = popped.extend_from_slice(&buf[..count]);
+    let pushed = thread.join().unwrap();
+
+    let count = consumer.pop_slice(&mut buf);
+    let () = popped.extend_from_slice(&buf[..count]);
+    assert!(popped.windows(2).all(|w| w[0] < w[1]));
+    if pushed == 1 {
+      assert_eq!(popped.last(), Some(&3));
+    }
+  });
+}
+
+
+/// Check that concurrent pushes and pops on an `MpmcRing` neither lose
+/// nor duplicate elements.
+#[test]
+fn mpmc_push_pop() {
+  loom::model(|| {
+    let ring = Arc::new(MpmcRing::new(2));
+    let threads = (0..2)
+      .map(|i| {
+        let ring = Arc::clone(&ring);
+        thread::spawn(move || {
+          assert_eq!(ring.try_push(i), Ok(()));
+          ring.try_pop()
+        })
+      })
+      .collect::<Vec<_>>();
+
+    let mut all = threads
+      .into_iter()
+      .filter_map(|thread| thread.join().unwrap())
+      .collect::<Vec<_>>();
+    let () = all.extend(ring.try_pop());
+    let () = all.extend(ring.try_pop());
+    let () = all.sort();
+    assert_eq!(all, [0, 1]);
+  });
+}
+
+/// Check that forcefully pushing to an `MpmcRing` concurrently with
+/// other operations neither loses nor duplicates elements.
+#[test]
+fn mpmc_force_push() {
+  loom::model(|| {
+    let ring = Arc::new(MpmcRing::new(1));
+    assert_eq!(ring.try_push(0), Ok(()));
+
+    let pusher = {
+      let ring = Arc::clone(&ring);
+      thread::spawn(move || ring.force_push(1))
+    };
+    let popper = {
+      let ring = Arc::clone(&ring);
+      thread::spawn(move || ring.try_pop())
+    };
+
+    let mut all = Vec::new();
+    let () = all.extend(pusher.join().unwrap());
+    let () = all.extend(popper.join().unwrap());
+    let () = all.extend(ring.try_pop());
+    let () = all.sort();
+    assert_eq!(all, [0, 1]);
+  });
+}
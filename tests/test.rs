@@ -3,11 +3,46 @@
 
 //! Integration tests for the `rbuf` crate.
 
+// Concurrent ring buffers only work inside a `loom` model when
+// building with `--cfg loom`. Those are covered by tests/loom.rs.
+#![cfg(not(loom))]
+
 use std::collections::VecDeque;
+use std::fmt::Write as _;
+#[cfg(feature = "std")]
+use std::io::BufRead as _;
+#[cfg(feature = "std")]
+use std::io::Read as _;
 use std::ops::Deref as _;
+#[cfg(all(feature = "shm", target_os = "linux"))]
+use std::process::Child;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::thread;
+#[cfg(feature = "std")]
+use std::time::Duration;
 
+use rbuf::broadcast::BroadcastRing;
+use rbuf::broadcast::Lagged;
 use rbuf::ring_buf;
+use rbuf::seqlock;
+use rbuf::spsc;
+use rbuf::spsc::Overflow;
+use rbuf::BitRingBuf;
+use rbuf::BoundedRing;
+use rbuf::ByteRing;
+#[cfg(feature = "std")]
+use rbuf::Closed;
+use rbuf::MpmcRing;
+use rbuf::Pow2RingBuf;
+use rbuf::RecordRing;
+use rbuf::RingArray;
 use rbuf::RingBuf;
+use rbuf::SparseRingBuf;
+use rbuf::Storage;
+#[cfg(feature = "std")]
+use rbuf::SyncRingBuf;
+use rbuf::TailWriter;
 
 
 #[test]
@@ -360,3 +395,1985 @@ fn boxed_slice() {
   let buf = RingBuf::from(slice);
   assert_eq!(buf, ring_buf![2, 3, 4, 5]);
 }
+
+/// Check that we can retrieve the contents of a `RingBuf` as a pair of
+/// slices.
+#[test]
+fn buf_slices() {
+  let mut buf = ring_buf![1, 2, 3, 4];
+  assert_eq!(buf.as_slices(), (&[1, 2, 3, 4][..], &[][..]));
+
+  let () = buf.push_back(5);
+  let () = buf.push_back(6);
+  assert_eq!(buf.as_slices(), (&[3, 4][..], &[5, 6][..]));
+
+  let (front, back) = buf.as_mut_slices();
+  front[0] = 7;
+  back[1] = 8;
+  assert_eq!(buf.make_contiguous(), &[7, 4, 5, 8]);
+}
+
+/// Check that push and pop operations on a `BoundedRing` work as
+/// expected and mirror those of a `VecDeque`.
+#[test]
+fn bounded_push_pop() {
+  let mut ring = BoundedRing::<usize>::new(3);
+  assert!(ring.is_empty());
+  assert!(!ring.is_full());
+  assert_eq!(ring.capacity(), 3);
+  assert_eq!(ring.front(), None);
+  assert_eq!(ring.back(), None);
+  assert_eq!(ring.pop_front(), None);
+  assert_eq!(ring.pop_back(), None);
+
+  assert_eq!(ring.push_back(1), None);
+  assert_eq!(ring.push_back(2), None);
+  assert_eq!(ring.push_front(0), None);
+  assert!(ring.is_full());
+  assert_eq!(ring.len(), 3);
+  assert_eq!(ring.front(), Some(&0));
+  assert_eq!(ring.back(), Some(&2));
+
+  assert_eq!(ring.push_back(3), Some(0));
+  assert_eq!(ring.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+  assert_eq!(ring.push_front(4), Some(3));
+  assert_eq!(ring.iter().copied().collect::<Vec<_>>(), vec![4, 1, 2]);
+
+  assert_eq!(ring.pop_back(), Some(2));
+  assert_eq!(ring.pop_front(), Some(4));
+  assert_eq!(ring.len(), 1);
+  assert_eq!(ring[0], 1);
+  assert_eq!(ring.get(1), None);
+
+  *ring.front_mut().unwrap() = 5;
+  assert_eq!(ring.pop_back(), Some(5));
+  assert!(ring.is_empty());
+}
+
+/// Make sure that the slice and iteration APIs of `BoundedRing` work
+/// correctly across the wrap around point.
+#[test]
+fn bounded_slices_iter() {
+  let mut ring = BoundedRing::new(4);
+  let mut deq = VecDeque::new();
+
+  for i in 0..6 {
+    let _evicted = ring.push_back(i);
+    let () = deq.push_back(i);
+    if deq.len() > 4 {
+      let _front = deq.pop_front();
+    }
+  }
+
+  assert_eq!(ring.as_slices(), (&[2, 3][..], &[4, 5][..]));
+  assert!(ring.iter().eq(deq.iter()));
+  assert!(ring.iter().rev().eq(deq.iter().rev()));
+  assert_eq!(ring.iter().len(), 4);
+
+  let () = ring.iter_mut().for_each(|x| *x *= 2);
+  assert_eq!(ring.make_contiguous(), &[4, 6, 8, 10]);
+  assert_eq!(ring.as_slices(), (&[4, 6, 8, 10][..], &[][..]));
+
+  let _front = ring.pop_front();
+  let _front = ring.pop_front();
+  let (front, back) = ring.as_mut_slices();
+  assert_eq!(front, &mut [8, 10]);
+  assert_eq!(back, &mut []);
+}
+
+/// Check that `BoundedRing` drops exactly the elements it contains.
+#[test]
+fn bounded_drop() {
+  let rc = Rc::new(());
+  let mut ring = BoundedRing::new(3);
+  for _ in 0..5 {
+    let _evicted = ring.push_back(Rc::clone(&rc));
+  }
+  assert_eq!(Rc::strong_count(&rc), 4);
+
+  let clone = ring.clone();
+  assert_eq!(clone, ring);
+  assert_eq!(Rc::strong_count(&rc), 7);
+
+  let () = ring.clear();
+  assert_eq!(Rc::strong_count(&rc), 4);
+
+  drop(clone);
+  assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+/// Check that users cannot create a mutable iterator over a
+/// `BoundedRing` containing objects of a zero sized type.
+#[test]
+#[should_panic(
+  expected = "Mutable iterators are not supported on ring buffers over zero sized types"
+)]
+fn bounded_no_mutable_iterator_with_zst() {
+  let mut ring = BoundedRing::new(2);
+  let _evicted = ring.push_back(());
+  let _it = ring.iter_mut();
+}
+
+/// Check that the helpers for `RingBuf<Option<T>>` work as expected.
+#[test]
+fn option_helpers() {
+  let mut buf = ring_buf![None, Some(1), None, Some(2), Some(3)];
+  let () = buf.push_back(None);
+  // [Some(1), None, Some(2), Some(3), None]
+
+  assert_eq!(buf.count_some(), 3);
+  assert_eq!(
+    buf.iter_some().collect::<Vec<_>>(),
+    vec![(0, &1), (2, &2), (3, &3)]
+  );
+  assert_eq!(
+    buf.iter_some().rev().collect::<Vec<_>>(),
+    vec![(3, &3), (2, &2), (0, &1)]
+  );
+  assert_eq!(buf.first_some(), Some((0, &1)));
+  assert_eq!(buf.last_some(), Some((3, &3)));
+
+  let () = buf.compact();
+  assert_eq!(
+    buf.iter().cloned().collect::<Vec<_>>(),
+    vec![None, None, Some(1), Some(2), Some(3)]
+  );
+
+  let mut it = buf.take_all();
+  assert_eq!(it.next(), Some(1));
+  assert_eq!(it.next_back(), Some(3));
+  drop(it);
+  assert_eq!(buf.count_some(), 0);
+  assert_eq!(buf.first_some(), None);
+  assert_eq!(buf.last_some(), None);
+}
+
+/// Check that basic operations on a `SparseRingBuf` work as expected
+/// and are consistent with those of a `RingBuf<Option<T>>`.
+#[test]
+fn sparse_ops() {
+  let mut sparse = SparseRingBuf::<u64>::new(5);
+  let mut buf = RingBuf::<Option<u64>>::new(5);
+  assert_eq!(sparse.len(), 5);
+  assert_eq!(sparse.count(), 0);
+  assert_eq!(sparse.front(), None);
+  assert_eq!(sparse.back(), None);
+
+  for elem in [Some(1), None, Some(3), Some(4), None, None, Some(7)] {
+    let () = sparse.push_back(elem);
+    let () = buf.push_back(elem);
+  }
+
+  for idx in 0..10 {
+    assert_eq!(sparse.get(idx), buf[idx].as_ref());
+  }
+  assert_eq!(sparse.count(), buf.count_some());
+  assert_eq!(sparse.front(), buf.front().as_ref());
+  assert_eq!(sparse.back(), buf.back().as_ref());
+
+  let () = sparse.push_front(Some(8));
+  let () = buf.push_front(Some(8));
+  assert_eq!(sparse.pop_back(), buf.pop_back());
+  assert_eq!(sparse.pop_front(), buf.pop_front());
+  assert_eq!(sparse.take(1), buf[1].take());
+  assert_eq!(sparse.replace(0, Some(9)), buf[0].replace(9));
+  *sparse.get_mut(0).unwrap() += 1;
+  *buf[0].as_mut().unwrap() += 1;
+
+  for idx in 0..5 {
+    assert_eq!(sparse.get(idx), buf[idx].as_ref());
+  }
+}
+
+/// Make sure that iteration over a `SparseRingBuf` skips unoccupied
+/// slots, also when spanning multiple bitmap words.
+#[test]
+fn sparse_iter() {
+  let mut sparse = SparseRingBuf::new(150);
+  let mut buf = RingBuf::new(150);
+
+  for i in 0..230usize {
+    let elem = if i % 7 == 0 || i % 11 == 0 {
+      Some(i)
+    } else {
+      None
+    };
+    let () = sparse.push_back(elem);
+    let () = buf.push_back(elem);
+  }
+
+  assert!(sparse.iter().eq(buf.iter_some()));
+  assert!(sparse.iter().rev().eq(buf.iter_some().rev()));
+
+  let mut it = sparse.iter();
+  let mut expected = buf.iter_some();
+  for _ in 0..10 {
+    assert_eq!(it.next(), expected.next());
+    assert_eq!(it.next_back(), expected.next_back());
+  }
+  assert!(it.eq(expected));
+
+  let () = sparse.iter_mut().for_each(|(_, elem)| *elem += 1);
+  assert!(sparse
+    .iter()
+    .map(|(idx, elem)| (idx, *elem))
+    .eq(buf.iter_some().map(|(idx, elem)| (idx, *elem + 1))));
+}
+
+/// Check that `SparseRingBuf` drops exactly the elements it contains.
+#[test]
+fn sparse_drop() {
+  let rc = Rc::new(());
+  let mut sparse = SparseRingBuf::new(3);
+  let () = sparse.push_back(Some(Rc::clone(&rc)));
+  let () = sparse.push_back(None);
+  let () = sparse.push_back(Some(Rc::clone(&rc)));
+  let () = sparse.push_back(Some(Rc::clone(&rc)));
+  assert_eq!(Rc::strong_count(&rc), 3);
+
+  let clone = sparse.clone();
+  assert_eq!(clone, sparse);
+  assert_eq!(Rc::strong_count(&rc), 5);
+
+  drop(sparse);
+  assert_eq!(Rc::strong_count(&rc), 3);
+  drop(clone);
+  assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+/// Check that a `BitRingBuf` behaves like a `RingBuf<bool>`.
+#[test]
+fn bit_ops() {
+  let mut bits = BitRingBuf::new(130);
+  let mut buf = RingBuf::<bool>::new(130);
+
+  for i in 0..300 {
+    let value = i % 3 == 0 || i % 5 == 0;
+    if i % 17 == 0 {
+      let () = bits.push_front(value);
+      let () = buf.push_front(value);
+    } else {
+      let () = bits.push_back(value);
+      let () = buf.push_back(value);
+    }
+  }
+
+  assert_eq!(bits.len(), 130);
+  assert_eq!(bits.front(), *buf.front());
+  assert_eq!(bits.back(), *buf.back());
+  assert!(bits.iter().eq(buf.iter().copied()));
+  assert!(bits.iter().rev().eq(buf.iter().rev().copied()));
+  assert_eq!(bits.iter().len(), 130);
+
+  for idx in 0..260 {
+    assert_eq!(bits.get(idx), buf[idx]);
+  }
+
+  assert_eq!(bits.set(3, true), buf[3]);
+  buf[3] = true;
+  assert_eq!(bits.pop_front(), buf.pop_front());
+  assert_eq!(bits.pop_back(), buf.pop_back());
+  assert!(bits.iter().eq(buf.iter().copied()));
+}
+
+/// Make sure that we can count set bits in a `BitRingBuf`, in total as
+/// well as in logical ranges.
+#[test]
+fn bit_count_ones() {
+  let mut bits = BitRingBuf::new(200);
+  let mut buf = RingBuf::<bool>::new(200);
+
+  for i in 0..333 {
+    let value = i % 7 < 3;
+    let () = bits.push_back(value);
+    let () = buf.push_back(value);
+  }
+
+  let count = |start: usize, end: usize| {
+    buf
+      .iter()
+      .skip(start)
+      .take(end - start)
+      .filter(|x| **x)
+      .count()
+  };
+
+  assert_eq!(bits.count_ones(), count(0, 200));
+  assert_eq!(bits.count_ones_range(..), count(0, 200));
+  assert_eq!(bits.count_ones_range(5..5), 0);
+
+  for (start, end) in [(0, 1), (0, 64), (1, 65), (60, 140), (66, 200), (199, 200), (3, 197)] {
+    assert_eq!(bits.count_ones_range(start..end), count(start, end));
+    assert_eq!(bits.count_ones_range(start..=end - 1), count(start, end));
+  }
+}
+
+/// Check that counting bits in an out-of-bounds range panics.
+#[test]
+#[should_panic(expected = "range end index 11 out of range for ring buffer of length 10")]
+fn bit_count_ones_out_of_bounds() {
+  let bits = BitRingBuf::new(10);
+  let _count = bits.count_ones_range(2..11);
+}
+
+/// Check that a `RingArray` behaves like a `RingBuf`.
+#[test]
+fn array_ops() {
+  let mut array = RingArray::from_array([0usize; 4]);
+  let mut buf = RingBuf::<usize>::new(4);
+  assert_eq!(array.len(), buf.len());
+
+  for i in 1..10 {
+    if i % 3 == 0 {
+      let () = array.push_front(i);
+      let () = buf.push_front(i);
+    } else {
+      let () = array.push_back(i);
+      let () = buf.push_back(i);
+    }
+    assert_eq!(array.front(), buf.front());
+    assert_eq!(array.back(), buf.back());
+    assert!(array.iter().eq(buf.iter()));
+    assert!(array.iter().rev().eq(buf.iter().rev()));
+    assert_eq!(array.as_slices().0.len() + array.as_slices().1.len(), 4);
+  }
+
+  for idx in 0..8 {
+    assert_eq!(array[idx], buf[idx]);
+  }
+
+  assert_eq!(array.pop_front(), buf.pop_front());
+  assert_eq!(array.pop_back(), buf.pop_back());
+  *array.front_mut() += 1;
+  *buf.front_mut() += 1;
+  *array.back_mut() += 1;
+  *buf.back_mut() += 1;
+  array[1] += 1;
+  buf[1] += 1;
+  let () = array.iter_mut().for_each(|x| *x *= 2);
+  let () = buf.iter_mut().for_each(|x| *x *= 2);
+
+  assert_eq!(array.make_contiguous(), buf.make_contiguous());
+  assert_eq!(array.into_storage().as_slice(), buf.into_boxed_slice().deref());
+}
+
+/// Make sure that a `RingArray` can be used in `static` context.
+#[test]
+fn array_static() {
+  static ARRAY: RingArray<u8, 3> = RingArray::from_array([3, 4, 5]);
+
+  assert_eq!(*ARRAY.front(), 3);
+  assert_eq!(ARRAY[2], 5);
+  assert!(ARRAY.iter().eq([3, 4, 5].iter()));
+}
+
+/// Check that a `RingBuf` can operate on various kinds of storage.
+#[test]
+fn storage() {
+  fn test<S>(mut buf: RingBuf<usize, S>) -> S
+  where
+    S: Storage<usize>,
+  {
+    let () = buf.push_back(4);
+    let () = buf.push_back(5);
+    let () = buf.push_front(6);
+    assert_eq!(buf.len(), 3);
+    assert_eq!(*buf.front(), 6);
+    assert_eq!(*buf.back(), 4);
+    assert!(buf.iter().eq([6, 3, 4].iter()));
+    buf.into_storage()
+  }
+
+  let mut storage = [1, 2, 3];
+  let slice = test(RingBuf::from_storage(&mut storage[..]));
+  assert_eq!(slice, &[6, 3, 4]);
+  // The ring buffer operated directly on the borrowed storage.
+  assert_eq!(storage, [6, 3, 4]);
+
+  let vec = test(RingBuf::from_storage(vec![1, 2, 3]));
+  assert_eq!(vec, vec![6, 3, 4]);
+
+  let array = test(RingBuf::from([1, 2, 3]));
+  assert_eq!(array, [6, 3, 4]);
+
+  let boxed = test(ring_buf![1, 2, 3]);
+  assert_eq!(boxed.deref(), &[6, 3, 4]);
+}
+
+/// Make sure that we cannot create a `RingBuf` from empty storage.
+#[test]
+#[should_panic]
+fn storage_empty() {
+  let _buf = RingBuf::<usize, _>::from_storage(Vec::new());
+}
+
+/// Check that a `Pow2RingBuf` behaves just like a `RingBuf`.
+#[test]
+fn pow2_ops() {
+  let mut buf = RingBuf::<usize>::new(8);
+  let mut pow2 = Pow2RingBuf::<usize>::new(8);
+
+  for i in 0..20 {
+    let () = buf.push_back(i);
+    let () = pow2.push_back(i);
+    assert_eq!(buf.front(), pow2.front());
+    assert_eq!(buf.back(), pow2.back());
+  }
+  assert!(buf.iter().eq(pow2.iter()));
+  assert!(buf.iter().rev().eq(pow2.iter().rev()));
+  assert_eq!(buf.as_slices(), pow2.as_slices());
+
+  for i in 0..20 {
+    assert_eq!(buf[i], pow2[i]);
+  }
+
+  let () = buf.push_front(42);
+  let () = pow2.push_front(42);
+  assert_eq!(buf.pop_back(), pow2.pop_back());
+  assert_eq!(buf.pop_front(), pow2.pop_front());
+  assert_eq!(buf.pop_front(), pow2.pop_front());
+
+  // Moving the front backwards from its initial position wraps the
+  // free-running counter of `Pow2RingBuf` around at zero.
+  let mut buf = RingBuf::<usize>::new(8);
+  let mut pow2 = Pow2RingBuf::<usize>::new(8);
+  for i in 0..20 {
+    let () = buf.push_front(i);
+    let () = pow2.push_front(i);
+    assert_eq!(buf.front(), pow2.front());
+    assert_eq!(buf.back(), pow2.back());
+  }
+  let () = buf.rotate_right(5);
+  let () = pow2.rotate_right(5);
+  assert_eq!(buf.as_slices(), pow2.as_slices());
+  let () = buf.rotate_left(7);
+  let () = pow2.rotate_left(7);
+  assert_eq!(buf.as_slices(), pow2.as_slices());
+  assert_eq!(buf.pop_back(), pow2.pop_back());
+  assert_eq!(buf.pop_front(), pow2.pop_front());
+
+  for (elem, other) in pow2.iter_mut().zip(buf.iter_mut()) {
+    *elem += 1;
+    *other += 1;
+  }
+  assert_eq!(
+    buf.clone().into_boxed_slice(),
+    pow2.clone().into_boxed_slice()
+  );
+  assert_eq!(buf.make_contiguous(), pow2.make_contiguous());
+}
+
+/// Check that `Pow2RingBuf` equality is independent of how the ring
+/// buffer got into its current state.
+#[test]
+fn pow2_eq() {
+  let mut buf1 = Pow2RingBuf::<usize>::from_vec(vec![1, 2, 3, 4]);
+  let mut buf2 = buf1.clone();
+
+  for _ in 0..4 {
+    let front = buf1[0];
+    let () = buf1.push_back(front);
+  }
+  assert_eq!(buf1, buf2);
+
+  let mut buf3 = buf2.clone();
+  let () = buf3.rotate_right(4);
+  assert_eq!(buf1, buf3);
+
+  let () = buf2.push_front(4);
+  assert_ne!(buf1, buf2);
+}
+
+/// Make sure that we cannot create a `Pow2RingBuf` with a length that
+/// is not a power of two.
+#[test]
+#[should_panic = "not a power of two"]
+fn pow2_invalid_len() {
+  let _buf = Pow2RingBuf::<usize>::new(3);
+}
+
+/// Check that readers of a `BroadcastRing` observe elements
+/// independently of each other.
+#[test]
+fn broadcast_read() {
+  let mut ring = BroadcastRing::new(3);
+  let mut reader1 = ring.reader();
+  assert_eq!(ring.read(&mut reader1), Ok(None));
+
+  assert_eq!(ring.push(1), None);
+  assert_eq!(ring.push(2), None);
+  let mut reader2 = ring.reader();
+  let mut reader3 = ring.reader_from_oldest();
+  assert_eq!(ring.pending(&reader1), 2);
+  assert_eq!(ring.pending(&reader2), 0);
+  assert_eq!(ring.pending(&reader3), 2);
+
+  assert_eq!(ring.read(&mut reader1), Ok(Some(&1)));
+  assert_eq!(ring.push(3), None);
+  assert_eq!(ring.read(&mut reader1), Ok(Some(&2)));
+  assert_eq!(ring.read(&mut reader1), Ok(Some(&3)));
+  assert_eq!(ring.read(&mut reader1), Ok(None));
+  assert_eq!(ring.read(&mut reader2), Ok(Some(&3)));
+  assert_eq!(ring.read(&mut reader2), Ok(None));
+  assert_eq!(ring.read(&mut reader3), Ok(Some(&1)));
+
+  let mut reader4 = reader3.clone();
+  assert_eq!(ring.read(&mut reader3), Ok(Some(&2)));
+  assert_eq!(ring.read(&mut reader4), Ok(Some(&2)));
+}
+
+/// Check that a reader falling behind the writer of a `BroadcastRing`
+/// is notified and resynchronized.
+#[test]
+fn broadcast_lagged() {
+  let mut ring = BroadcastRing::new(2);
+  let mut reader = ring.reader();
+
+  for i in 0..5 {
+    let _evicted = ring.push(i);
+  }
+  assert_eq!(ring.len(), 2);
+  assert_eq!(ring.pending(&reader), 2);
+  assert_eq!(ring.read(&mut reader), Err(Lagged(3)));
+  assert_eq!(ring.read(&mut reader), Ok(Some(&3)));
+
+  assert_eq!(ring.push(5), Some(3));
+  assert_eq!(ring.push(6), Some(4));
+  assert_eq!(ring.read(&mut reader), Err(Lagged(1)));
+  assert_eq!(ring.read(&mut reader), Ok(Some(&5)));
+  assert_eq!(ring.read(&mut reader), Ok(Some(&6)));
+  assert_eq!(ring.read(&mut reader), Ok(None));
+  assert_eq!(Lagged(3).to_string(), "reader lagged behind by 3 elements");
+}
+
+/// Check the basic workings of a seqlock-based ring buffer.
+#[test]
+fn seqlock_ops() {
+  let (mut writer, reader) = seqlock::channel(3);
+  assert_eq!(reader.capacity(), 3);
+  assert_eq!(reader.latest(), None);
+  assert_eq!(reader.get(0), None);
+  assert_eq!(reader.snapshot(2), Vec::<usize>::new());
+
+  for i in 0..5 {
+    let () = writer.push(i);
+    assert_eq!(reader.latest(), Some(i));
+  }
+  assert_eq!(writer.pushed(), 5);
+  assert_eq!(reader.pushed(), 5);
+
+  assert_eq!(reader.get(1), None);
+  assert_eq!(reader.get(2), Some(2));
+  assert_eq!(reader.get(4), Some(4));
+  assert_eq!(reader.get(5), None);
+  assert_eq!(reader.range(2..5), Some(vec![2, 3, 4]));
+  assert_eq!(reader.range(1..3), None);
+  assert_eq!(reader.range(4..6), None);
+  assert_eq!(reader.range(0..usize::MAX), None);
+  assert_eq!(reader.snapshot(2), vec![3, 4]);
+  assert_eq!(reader.clone().snapshot(10), vec![2, 3, 4]);
+}
+
+/// Make sure that readers of a seqlock-based ring buffer never observe
+/// torn writes.
+// Seqlock reads race with writes by design, which Miri flags.
+#[cfg_attr(miri, ignore)]
+#[test]
+fn seqlock_threads() {
+  const COUNT: u64 = 100_000;
+
+  let (mut writer, reader) = seqlock::channel::<[u64; 8]>(4);
+  let readers = (0..2)
+    .map(|_| {
+      let reader = reader.clone();
+      thread::spawn(move || {
+        let mut last = 0;
+        while last != COUNT - 1 {
+          let snapshot = reader.snapshot(4);
+          for window in snapshot.windows(2) {
+            assert_eq!(window[0][0] + 1, window[1][0]);
+          }
+
+          for value in snapshot {
+            assert!(value.iter().all(|x| *x == value[0]));
+            last = value[0];
+          }
+          let () = thread::yield_now();
+        }
+      })
+    })
+    .collect::<Vec<_>>();
+
+  for i in 0..COUNT {
+    let () = writer.push([i; 8]);
+    if i % 1024 == 0 {
+      let () = thread::yield_now();
+    }
+  }
+
+  let () = readers
+    .into_iter()
+    .for_each(|thread| thread.join().unwrap());
+}
+
+/// Check the basic workings of a `SyncRingBuf`.
+#[cfg(feature = "std")]
+#[test]
+fn sync_ops() {
+  let buf = SyncRingBuf::new(2);
+  assert_eq!(buf.capacity(), 2);
+  assert!(buf.is_empty());
+  assert_eq!(buf.try_pop(), None);
+  assert_eq!(buf.pop_timeout(Duration::from_millis(1)), None);
+
+  assert_eq!(buf.push(1), Ok(None));
+  assert_eq!(buf.push_blocking(2), Ok(()));
+  assert_eq!(buf.push(3), Ok(Some(1)));
+  assert_eq!(buf.len(), 2);
+  assert_eq!(buf.wait_for_len(2), 2);
+  assert_eq!(buf.pop_blocking(), Some(2));
+  assert_eq!(buf.pop_timeout(Duration::from_millis(1)), Some(3));
+
+  assert_eq!(buf.push(4), Ok(None));
+  let () = buf.close();
+  assert!(buf.is_closed());
+  assert_eq!(buf.push(5), Err(Closed(5)));
+  assert_eq!(buf.push_blocking(5), Err(Closed(5)));
+  assert_eq!(buf.wait_for_len(2), 1);
+  assert_eq!(buf.pop_blocking(), Some(4));
+  assert_eq!(buf.pop_blocking(), None);
+  assert_eq!(Closed(5).to_string(), "ring buffer is closed");
+}
+
+/// Check that threads blocked on a `SyncRingBuf` get woken up.
+#[cfg(feature = "std")]
+#[test]
+fn sync_threads() {
+  const COUNT: usize = if cfg!(miri) { 50 } else { 10_000 };
+
+  let buf = Arc::new(SyncRingBuf::new(4));
+  let waiter = {
+    let buf = Arc::clone(&buf);
+    thread::spawn(move || buf.wait_for_len(3))
+  };
+  let producer = {
+    let buf = Arc::clone(&buf);
+    thread::spawn(move || {
+      for i in 0..COUNT {
+        let () = buf.push_blocking(i).unwrap();
+      }
+    })
+  };
+
+  assert!(waiter.join().unwrap() >= 3);
+  for i in 0..COUNT {
+    assert_eq!(buf.pop_blocking(), Some(i));
+  }
+  let () = producer.join().unwrap();
+
+  let closer = {
+    let buf = Arc::clone(&buf);
+    thread::spawn(move || {
+      let () = thread::sleep(Duration::from_millis(10));
+      let () = buf.close();
+    })
+  };
+  assert_eq!(buf.pop_blocking(), None);
+  let () = closer.join().unwrap();
+}
+
+/// Make sure that `SyncRingBuf::wait_for_len` rejects lengths exceeding
+/// the capacity.
+#[cfg(feature = "std")]
+#[test]
+#[should_panic = "exceeds ring buffer capacity"]
+fn sync_wait_for_len_too_large() {
+  let buf = SyncRingBuf::<usize>::new(2);
+  let _len = buf.wait_for_len(3);
+}
+
+/// Check the basic workings of an asynchronous channel.
+#[cfg(feature = "futures")]
+#[test]
+fn async_channel_ops() {
+  use futures::executor::block_on;
+  use futures::stream::iter;
+  use futures::SinkExt as _;
+  use futures::StreamExt as _;
+
+  use rbuf::async_channel::channel;
+
+  let (mut sender, mut receiver) = channel(2);
+  assert_eq!(receiver.try_recv(), None);
+  assert_eq!(sender.send(1), Ok(None));
+  assert_eq!(sender.send(2), Ok(None));
+  assert_eq!(sender.send(3), Ok(Some(1)));
+  assert_eq!(receiver.len(), 2);
+  assert_eq!(receiver.try_recv(), Some(2));
+
+  let () = block_on(async {
+    let () = sender.send_all(&mut iter([4, 5].map(Ok))).await.unwrap();
+    assert_eq!(receiver.next().await, Some(4));
+    assert_eq!(receiver.next().await, Some(5));
+  });
+
+  let sender2 = sender.clone();
+  drop(sender);
+  assert_eq!(sender2.send(6), Ok(None));
+  drop(sender2);
+  assert_eq!(block_on(receiver.collect::<Vec<_>>()), vec![6]);
+
+  let (sender, receiver) = channel(2);
+  drop(receiver);
+  assert!(sender.is_closed());
+  assert_eq!(sender.send(1), Err(Closed(1)));
+}
+
+/// Check that a task waiting on an asynchronous channel gets woken up
+/// when elements arrive.
+#[cfg(feature = "futures")]
+#[test]
+fn async_channel_wake() {
+  use futures::executor::LocalPool;
+  use futures::task::LocalSpawnExt as _;
+  use futures::StreamExt as _;
+
+  use rbuf::async_channel::channel;
+
+  let mut pool = LocalPool::new();
+  let (sender, receiver) = channel(4);
+  let received = pool
+    .spawner()
+    .spawn_local_with_handle(receiver.collect::<Vec<_>>())
+    .unwrap();
+
+  let () = pool.run_until_stalled();
+  for i in 0..3 {
+    assert_eq!(sender.send(i), Ok(None));
+    let () = pool.run_until_stalled();
+  }
+  drop(sender);
+
+  assert_eq!(pool.run_until(received), vec![0, 1, 2]);
+}
+
+/// Check the basic workings of an SPSC ring buffer rejecting elements
+/// when full.
+#[test]
+fn spsc_reject() {
+  let (mut producer, mut consumer) = spsc::channel(3, Overflow::Reject);
+  assert_eq!(producer.capacity(), 3);
+  assert!(consumer.is_empty());
+  assert_eq!(consumer.pop(), None);
+
+  assert_eq!(producer.push(1), Ok(None));
+  assert_eq!(producer.push(2), Ok(None));
+  assert_eq!(producer.push(3), Ok(None));
+  assert_eq!(producer.push(4), Err(4));
+  assert_eq!(consumer.len(), 3);
+
+  assert_eq!(consumer.pop(), Some(1));
+  assert_eq!(producer.push(4), Ok(None));
+  assert_eq!(consumer.pop(), Some(2));
+  assert_eq!(consumer.pop(), Some(3));
+  assert_eq!(consumer.pop(), Some(4));
+  assert_eq!(consumer.pop(), None);
+}
+
+/// Check the basic workings of an SPSC ring buffer overwriting the
+/// oldest element when full.
+#[test]
+fn spsc_overwrite() {
+  let (mut producer, mut consumer) = spsc::channel(2, Overflow::Overwrite);
+  assert_eq!(producer.push(1), Ok(None));
+  assert_eq!(producer.push(2), Ok(None));
+  assert_eq!(producer.push(3), Ok(Some(1)));
+  assert_eq!(producer.push(4), Ok(Some(2)));
+  assert_eq!(consumer.pop(), Some(3));
+  assert_eq!(producer.push(5), Ok(None));
+  assert_eq!(producer.push(6), Ok(Some(4)));
+  assert_eq!(consumer.pop(), Some(5));
+  assert_eq!(consumer.pop(), Some(6));
+  assert_eq!(consumer.pop(), None);
+}
+
+/// Check that batch operations on an SPSC ring buffer work as expected.
+#[test]
+fn spsc_slices() {
+  let (mut producer, mut consumer) = spsc::channel(4, Overflow::Reject);
+  assert_eq!(producer.push_slice(&[1, 2, 3]), 3);
+  assert_eq!(producer.push_slice(&[4, 5, 6]), 1);
+
+  let mut buf = [0; 3];
+  assert_eq!(consumer.pop_slice(&mut buf), 3);
+  assert_eq!(buf, [1, 2, 3]);
+  assert_eq!(producer.push_slice(&[5, 6]), 2);
+  assert_eq!(consumer.pop_slice(&mut buf), 3);
+  assert_eq!(buf, [4, 5, 6]);
+  assert_eq!(consumer.pop_slice(&mut buf), 0);
+
+  let (mut producer, mut consumer) = spsc::channel(4, Overflow::Overwrite);
+  assert_eq!(producer.push_slice(&[1, 2, 3]), 3);
+  assert_eq!(producer.push_slice(&[4, 5, 6]), 3);
+  assert_eq!(producer.push_slice(&[7, 8, 9, 10, 11, 12]), 6);
+
+  let mut buf = [0; 5];
+  assert_eq!(consumer.pop_slice(&mut buf), 4);
+  assert_eq!(buf[..4], [9, 10, 11, 12]);
+}
+
+/// Make sure that elements remaining in an SPSC ring buffer are dropped
+/// along with it.
+#[test]
+fn spsc_drop() {
+  let rc = Rc::new(());
+  let (mut producer, mut consumer) = spsc::channel(3, Overflow::Overwrite);
+  for _ in 0..5 {
+    let _evicted = producer.push(Rc::clone(&rc)).unwrap();
+  }
+  let _elem = consumer.pop().unwrap();
+  assert_eq!(Rc::strong_count(&rc), 4);
+
+  drop(producer);
+  drop(consumer);
+  assert_eq!(Rc::strong_count(&rc), 2);
+}
+
+/// Exchange a larger number of elements between two threads through an
+/// SPSC ring buffer.
+#[test]
+fn spsc_threads() {
+  const COUNT: usize = if cfg!(miri) { 100 } else { 100_000 };
+
+  for overflow in [Overflow::Reject, Overflow::Overwrite] {
+    let (mut producer, mut consumer) = spsc::channel(16, overflow);
+    let thread = thread::spawn(move || {
+      let mut i = 0;
+      while i < COUNT {
+        let count = if i % 3 == 0 {
+          let end = (i + 5).min(COUNT);
+          let elems = (i..end).collect::<Vec<_>>();
+          producer.push_slice(&elems)
+        } else {
+          usize::from(producer.push(i).is_ok())
+        };
+
+        if count == 0 {
+          let () = thread::yield_now();
+        }
+        i += count;
+      }
+    });
+
+    let mut last = None;
+    let mut buf = [0; 4];
+    while last != Some(COUNT - 1) {
+      let count = consumer.pop_slice(&mut buf);
+      if count == 0 {
+        let () = thread::yield_now();
+      }
+
+      for &i in &buf[..count] {
+        match overflow {
+          Overflow::Reject => assert_eq!(i, last.map_or(0, |last| last + 1)),
+          Overflow::Overwrite => assert!(last < Some(i)),
+        }
+        last = Some(i);
+      }
+    }
+    let () = thread.join().unwrap();
+  }
+}
+
+/// Check the basic workings of an `MpmcRing`.
+#[test]
+fn mpmc_ops() {
+  let ring = MpmcRing::new(3);
+  assert_eq!(ring.capacity(), 3);
+  assert!(ring.is_empty());
+  assert_eq!(ring.try_pop(), None);
+
+  for i in 0..10 {
+    assert_eq!(ring.try_push(i), Ok(()));
+    assert_eq!(ring.try_push(i + 1), Ok(()));
+    assert_eq!(ring.len(), 2);
+    assert_eq!(ring.try_pop(), Some(i));
+    assert_eq!(ring.try_pop(), Some(i + 1));
+  }
+
+  assert_eq!(ring.try_push(1), Ok(()));
+  assert_eq!(ring.try_push(2), Ok(()));
+  assert_eq!(ring.try_push(3), Ok(()));
+  assert!(ring.is_full());
+  assert_eq!(ring.try_push(4), Err(4));
+  assert_eq!(ring.force_push(4), Some(1));
+  assert_eq!(ring.force_push(5), Some(2));
+  assert_eq!(ring.len(), 3);
+  assert_eq!(ring.try_pop(), Some(3));
+  assert_eq!(ring.force_push(6), None);
+  assert_eq!(ring.try_pop(), Some(4));
+  assert_eq!(ring.try_pop(), Some(5));
+  assert_eq!(ring.try_pop(), Some(6));
+  assert_eq!(ring.try_pop(), None);
+}
+
+/// Make sure that elements remaining in an `MpmcRing` are dropped
+/// along with it.
+#[test]
+fn mpmc_drop() {
+  let rc = Rc::new(());
+  let ring = MpmcRing::new(3);
+  for _ in 0..5 {
+    let _evicted = ring.force_push(Rc::clone(&rc));
+  }
+  let _elem = ring.try_pop().unwrap();
+  assert_eq!(Rc::strong_count(&rc), 4);
+
+  drop(ring);
+  assert_eq!(Rc::strong_count(&rc), 2);
+}
+
+/// Exchange elements between multiple producer and consumer threads
+/// through an `MpmcRing`, making sure that each element is received
+/// exactly once.
+#[test]
+fn mpmc_threads() {
+  const THREADS: usize = 3;
+  const COUNT: usize = if cfg!(miri) { 50 } else { 10_000 };
+
+  let ring = Arc::new(MpmcRing::new(8));
+  let producers = (0..THREADS)
+    .map(|t| {
+      let ring = Arc::clone(&ring);
+      thread::spawn(move || {
+        for i in 0..COUNT {
+          let mut elem = t * COUNT + i;
+          while let Err(e) = ring.try_push(elem) {
+            elem = e;
+            let () = thread::yield_now();
+          }
+        }
+      })
+    })
+    .collect::<Vec<_>>();
+  let consumers = (0..THREADS)
+    .map(|_| {
+      let ring = Arc::clone(&ring);
+      thread::spawn(move || {
+        let mut received = Vec::new();
+        while received.len() < COUNT {
+          if let Some(elem) = ring.try_pop() {
+            let () = received.push(elem);
+          } else {
+            let () = thread::yield_now();
+          }
+        }
+        received
+      })
+    })
+    .collect::<Vec<_>>();
+
+  let () = producers
+    .into_iter()
+    .for_each(|thread| thread.join().unwrap());
+  let mut received = consumers
+    .into_iter()
+    .flat_map(|thread| thread.join().unwrap())
+    .collect::<Vec<_>>();
+  let () = received.sort_unstable();
+  assert_eq!(received, (0..THREADS * COUNT).collect::<Vec<_>>());
+}
+
+/// Check that rotating a `RingBuf` adjusts its logical order.
+#[test]
+fn buf_rotate() {
+  let mut buf = ring_buf![1, 2, 3, 4, 5];
+  let () = buf.rotate_left(2);
+  assert_eq!(buf.iter().copied().collect::<Vec<_>>(), vec![3, 4, 5, 1, 2]);
+  let () = buf.rotate_right(3);
+  assert_eq!(buf.iter().copied().collect::<Vec<_>>(), vec![5, 1, 2, 3, 4]);
+  let () = buf.rotate_left(5);
+  assert_eq!(buf.iter().copied().collect::<Vec<_>>(), vec![5, 1, 2, 3, 4]);
+}
+
+/// Make sure that rotating a `RingBuf` by more than its length panics.
+#[test]
+#[should_panic(expected = "exceeds ring buffer length")]
+fn buf_rotate_too_far() {
+  let mut buf = ring_buf![1, 2, 3];
+  let () = buf.rotate_left(4);
+}
+
+/// Check that a `ByteRing` hands out readable and free space correctly
+/// across the wrap around point.
+#[test]
+fn byte_ring_slices() {
+  let mut ring = ByteRing::new(6);
+  assert!(ring.is_empty());
+  assert_eq!(ring.as_slices(), (&b""[..], &b""[..]));
+  assert_eq!(ring.push_slice(b"abcd"), 4);
+  assert_eq!(ring.as_slices(), (&b"abcd"[..], &b""[..]));
+
+  let () = ring.consume(3);
+  assert_eq!(ring.push_slice(b"efghij"), 5);
+  assert!(ring.is_full());
+  assert_eq!(ring.as_slices(), (&b"def"[..], &b"ghi"[..]));
+
+  let mut buf = [0; 4];
+  assert_eq!(ring.pop_slice(&mut buf), 4);
+  assert_eq!(&buf, b"defg");
+  assert_eq!(ring.len(), 2);
+  assert_eq!(ring.free(), 4);
+
+  let (first, second) = ring.spare_slices_mut();
+  assert_eq!(first.len() + second.len(), 4);
+  let () = first.fill(b'x');
+  let () = second.fill(b'x');
+  let () = ring.commit(4);
+
+  let (first, second) = ring.as_slices();
+  assert_eq!([first, second].concat(), b"hixxxx");
+
+  let () = ring.clear();
+  assert!(ring.is_empty());
+  assert_eq!(ring.pop_slice(&mut buf), 0);
+}
+
+/// Check that `ByteRing` equality only considers the readable bytes.
+#[test]
+fn byte_ring_eq() {
+  let mut ring1 = ByteRing::new(4);
+  let mut ring2 = ByteRing::new(8);
+  assert_eq!(ring1, ring2);
+
+  assert_eq!(ring1.push_slice(b"xyab"), 4);
+  let () = ring1.consume(2);
+  assert_eq!(ring1.push_slice(b"c"), 1);
+  assert_eq!(ring2.push_slice(b"abc"), 3);
+  assert_eq!(ring1, ring2);
+
+  let () = ring2.consume(1);
+  assert_ne!(ring1, ring2);
+
+  let () = ring1.clear();
+  let () = ring2.clear();
+  assert_eq!(ring1, ring2);
+}
+
+/// Check that a `ByteRing` works as a pipe through its `io` trait
+/// implementations.
+#[cfg(feature = "std")]
+#[test]
+fn byte_ring_io() {
+  use std::io::Write as _;
+
+  let mut ring = ByteRing::new(8);
+  assert_eq!(ring.write(b"hello world").unwrap(), 8);
+  assert_eq!(ring.write(b"!").unwrap(), 0);
+
+  let mut buf = [0; 6];
+  let () = ring.read_exact(&mut buf).unwrap();
+  assert_eq!(&buf, b"hello ");
+
+  let () = ring.write_all(b"xyz").unwrap();
+  assert_eq!(ring.fill_buf().unwrap(), b"wo");
+  let () = ring.consume(2);
+  assert_eq!(ring.fill_buf().unwrap(), b"xyz");
+
+  let mut line = String::new();
+  let mut ring = ByteRing::new(16);
+  let () = ring.write_all(b"ab\ncd").unwrap();
+  assert_eq!(ring.read_line(&mut line).unwrap(), 3);
+  assert_eq!(line, "ab\n");
+
+  let mut rest = Vec::new();
+  assert_eq!(ring.read_to_end(&mut rest).unwrap(), 2);
+  assert_eq!(rest, b"cd");
+}
+
+/// Check that `ByteRing::read_from` fills both free regions.
+#[cfg(feature = "std")]
+#[test]
+fn byte_ring_read_from() {
+  let mut ring = ByteRing::new(8);
+  let () = ring.commit(5);
+  let () = ring.consume(5);
+
+  let mut reader = &b"0123456789"[..];
+  let mut total = 0;
+  while !ring.is_full() {
+    total += ring.read_from(&mut reader).unwrap();
+  }
+  assert_eq!(total, 8);
+  assert_eq!(reader, b"89");
+
+  let (first, second) = ring.as_slices();
+  assert_eq!(first, b"012");
+  assert_eq!(second, b"34567");
+  assert_eq!(ring.read_from(&mut reader).unwrap(), 0);
+}
+
+/// Check that a `TailWriter` retains only the most recently written
+/// bytes, including for writes wrapping around and exceeding its
+/// capacity.
+#[test]
+fn tail_writer_retain() {
+  let mut tail = TailWriter::new(5);
+  assert!(tail.is_empty());
+  assert_eq!(tail.contents(), (&b""[..], &b""[..]));
+
+  let () = tail.push_slice(b"abc");
+  assert_eq!(tail.contents(), (&b"abc"[..], &b""[..]));
+  assert!(!tail.is_truncated());
+
+  let () = tail.push_slice(b"defg");
+  assert_eq!(tail.len(), 5);
+  assert!(tail.is_truncated());
+  let (first, second) = tail.contents();
+  assert_eq!([first, second].concat(), b"cdefg");
+
+  let () = tail.push_slice(b"0123456789");
+  let (first, second) = tail.contents();
+  assert_eq!([first, second].concat(), b"56789");
+
+  let () = tail.clear();
+  assert!(tail.is_empty());
+  assert!(!tail.is_truncated());
+  let () = write!(tail, "{}-{}", 1, 2).unwrap();
+  assert_eq!(tail.to_string_lossy(), "1-2");
+}
+
+/// Check that `TailWriter` equality only considers the retained bytes
+/// and the truncation state.
+#[test]
+fn tail_writer_eq() {
+  let mut tail1 = TailWriter::new(4);
+  let mut tail2 = TailWriter::new(8);
+  assert_eq!(tail1, tail2);
+
+  let () = tail1.push_slice(b"xyz");
+  let () = tail1.clear();
+  let () = tail1.push_slice(b"ab");
+  let () = tail2.push_slice(b"ab");
+  assert_eq!(tail1, tail2);
+
+  let () = tail1.push_slice(b"cde");
+  let () = tail2.push_slice(b"cde");
+  // Only the first one had to discard data.
+  assert_ne!(tail1, tail2);
+}
+
+/// Make sure that `TailWriter::to_string_lossy` drops the remainder of
+/// a character split by truncation, but not invalid data otherwise.
+#[test]
+fn tail_writer_string_lossy() {
+  let mut tail = TailWriter::new(4);
+  let () = tail.write_str("h\u{e9}llo").unwrap();
+  assert_eq!(tail.to_string_lossy(), "llo");
+
+  let () = tail.write_str("\u{20ac}").unwrap();
+  assert_eq!(tail.to_string_lossy(), "o\u{20ac}");
+
+  let mut tail = TailWriter::new(4);
+  let () = tail.push_slice(b"\xa9ab");
+  assert_eq!(tail.to_string_lossy(), "\u{fffd}ab");
+}
+
+/// Check that a `TailWriter` can be used as an `io::Write` sink.
+#[cfg(feature = "std")]
+#[test]
+fn tail_writer_io() {
+  use std::io::copy;
+
+  let mut tail = TailWriter::new(6);
+  let () = copy(&mut &b"some lengthy error output"[..], &mut tail)
+    .map(|_| ())
+    .unwrap();
+  assert_eq!(tail.to_string_lossy(), "output");
+}
+
+/// Check that we can perform vectored I/O on a `RingBuf<u8>` without
+/// rearranging it.
+#[cfg(feature = "std")]
+#[test]
+fn buf_io_slices() {
+  use std::io::Read as _;
+  use std::io::Write as _;
+
+  let mut buf = RingBuf::from_vec(b"abcdef".to_vec());
+  let () = buf.rotate_left(4);
+
+  let mut out = Vec::new();
+  let n = out.write_vectored(&buf.io_slices()).unwrap();
+  assert_eq!(n, 6);
+  assert_eq!(out, b"efabcd");
+
+  let mut reader = &b"0123456789"[..];
+  let n = reader.read_vectored(&mut buf.io_slices_mut()).unwrap();
+  assert_eq!(n, 6);
+  assert_eq!(buf.iter().copied().collect::<Vec<_>>(), b"012345");
+  assert_eq!(*buf.front(), b'0');
+}
+
+/// Check that a `ByteRing` can be drained and refilled with vectored
+/// I/O, across its wrap around point.
+#[cfg(feature = "std")]
+#[test]
+fn byte_ring_write_to() {
+  let mut ring = ByteRing::new(8);
+  let () = ring.commit(6);
+  let () = ring.consume(6);
+  assert_eq!(ring.push_slice(b"abcdef"), 6);
+  assert_eq!(ring.io_slices_mut().iter().map(|slice| slice.len()).sum::<usize>(), 2);
+
+  let mut out = Vec::new();
+  let mut total = 0;
+  while !ring.is_empty() {
+    total += ring.write_to(&mut out).unwrap();
+  }
+  assert_eq!(total, 6);
+  assert_eq!(out, b"abcdef");
+  assert_eq!(ring.io_slices().iter().map(|slice| slice.len()).sum::<usize>(), 0);
+}
+
+/// Check that a `ByteRing` can be used through the `bytes` crate's
+/// `Buf` and `BufMut` traits.
+#[cfg(feature = "bytes")]
+#[test]
+fn byte_ring_bytes() {
+  use bytes::Buf as _;
+  use bytes::BufMut as _;
+
+  let mut ring = ByteRing::new(8);
+  let () = ring.commit(6);
+  let () = ring.consume(6);
+  assert_eq!(ring.remaining_mut(), 8);
+
+  let () = ring.put_u32(0xdeadbeef);
+  let () = ring.put_slice(b"abcd");
+  assert_eq!(ring.remaining_mut(), 0);
+  assert_eq!(ring.remaining(), 8);
+  assert_eq!(ring.chunk(), [0xde, 0xad]);
+
+  assert_eq!(ring.get_u32(), 0xdeadbeef);
+  assert_eq!(ring.chunk(), b"abcd");
+  let () = ring.advance(1);
+  assert_eq!(ring.copy_to_bytes(3), &b"bcd"[..]);
+  assert!(!ring.has_remaining());
+}
+
+/// Check that `Buf::chunks_vectored` exposes both readable regions of
+/// a `ByteRing`.
+#[cfg(all(feature = "bytes", feature = "std"))]
+#[test]
+fn byte_ring_bytes_vectored() {
+  use bytes::Buf as _;
+  use std::io::IoSlice;
+
+  let mut ring = ByteRing::new(4);
+  assert_eq!(ring.chunks_vectored(&mut [IoSlice::new(&[]); 4]), 0);
+
+  let () = ring.commit(3);
+  let () = ring.consume(3);
+  assert_eq!(ring.push_slice(b"wxyz"), 4);
+
+  let mut dst = [IoSlice::new(&[]); 4];
+  assert_eq!(ring.chunks_vectored(&mut dst), 2);
+  assert_eq!(&*dst[0], b"w");
+  assert_eq!(&*dst[1], b"xyz");
+  assert_eq!(ring.chunks_vectored(&mut dst[..1]), 1);
+}
+
+/// Check that data written to a `pipe` arrives in order, with the
+/// writer waiting for the reader to make space.
+#[cfg(feature = "tokio")]
+#[test]
+fn pipe_transfer() {
+  use futures::executor::block_on;
+  use futures::future::join;
+  use rbuf::pipe::pipe;
+  use tokio::io::AsyncReadExt as _;
+  use tokio::io::AsyncWriteExt as _;
+
+  let data = (0..=255).cycle().take(1000).collect::<Vec<u8>>();
+  let (mut writer, mut reader) = pipe(7);
+
+  let write = async {
+    let () = writer.write_all(&data).await.unwrap();
+    let () = writer.shutdown().await.unwrap();
+  };
+  let read = async {
+    let mut received = Vec::new();
+    let _n = reader.read_to_end(&mut received).await.unwrap();
+    received
+  };
+
+  let ((), received) = block_on(join(write, read));
+  assert_eq!(received, data);
+}
+
+/// Make sure that writing to a `pipe` whose reader is gone fails.
+#[cfg(feature = "tokio")]
+#[test]
+fn pipe_broken() {
+  use std::io::ErrorKind;
+
+  use futures::executor::block_on;
+  use rbuf::pipe::pipe;
+  use tokio::io::AsyncWriteExt as _;
+
+  let (mut writer, reader) = pipe(4);
+  let () = block_on(writer.write_all(b"abcd")).unwrap();
+  drop(reader);
+
+  let err = block_on(writer.write_all(b"e")).unwrap_err();
+  assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+}
+
+/// Make sure that writes to a shut down `PipeWriter` fail, while the
+/// reader still sees the data written before, followed by end-of-file.
+#[cfg(feature = "tokio")]
+#[test]
+fn pipe_shutdown() {
+  use std::io::ErrorKind;
+
+  use futures::executor::block_on;
+  use rbuf::pipe::pipe;
+  use tokio::io::AsyncReadExt as _;
+  use tokio::io::AsyncWriteExt as _;
+
+  let (mut writer, mut reader) = pipe(4);
+  let () = block_on(writer.write_all(b"ab")).unwrap();
+  let () = block_on(writer.shutdown()).unwrap();
+
+  let err = block_on(writer.write_all(b"c")).unwrap_err();
+  assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+
+  let mut data = Vec::new();
+  let _n = block_on(reader.read_to_end(&mut data)).unwrap();
+  assert_eq!(data, b"ab");
+}
+
+/// Check that both ends of a `duplex` pipe can talk to each other.
+#[cfg(feature = "tokio")]
+#[test]
+fn pipe_duplex() {
+  use futures::executor::block_on;
+  use rbuf::pipe::duplex;
+  use tokio::io::AsyncReadExt as _;
+  use tokio::io::AsyncWriteExt as _;
+
+  let (mut left, mut right) = duplex(8);
+  let () = block_on(async {
+    let () = left.write_all(b"ping").await.unwrap();
+    let mut buf = [0; 4];
+    let _n = right.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"ping");
+
+    let () = right.write_all(b"pong").await.unwrap();
+    drop(right);
+    let mut buf = Vec::new();
+    let _n = left.read_to_end(&mut buf).await.unwrap();
+    assert_eq!(buf, b"pong");
+  });
+}
+
+/// Check that a `MirroredRingBuf` always provides its contents as a
+/// contiguous slice, in logical order.
+#[cfg(all(feature = "mirrored", target_os = "linux"))]
+#[cfg_attr(miri, ignore)]
+#[test]
+fn mirrored_contiguous() {
+  use rbuf::MirroredRingBuf;
+
+  let mut buf = MirroredRingBuf::<u64>::new(3).unwrap();
+  let len = buf.len();
+  assert!(len >= 3);
+  assert!(buf.iter().all(|x| *x == 0));
+
+  let mut expected = VecDeque::from(vec![0; len]);
+  for i in 1..(2 * len as u64 + 5) {
+    let () = buf.push_back(i);
+    let _front = expected.pop_front();
+    let () = expected.push_back(i);
+    assert_eq!(*buf.back(), i);
+  }
+  assert_eq!(buf.as_slice(), expected.make_contiguous());
+  assert_eq!(buf[0], *buf.front());
+  assert_eq!(buf[len], *buf.front());
+
+  let () = buf.push_front(42);
+  assert_eq!(*buf.front(), 42);
+  assert_eq!(buf[1], expected[0]);
+
+  buf[len - 1] = 1337;
+  let () = buf.as_mut_slice()[1] += 1;
+  assert_eq!(*buf.back(), 1337);
+  assert_eq!(buf.as_slice()[1], expected[0] + 1);
+}
+
+/// Make sure that a `MirroredRingBuf` rounds its length up such that it
+/// spans whole pages, even for odd element sizes.
+#[cfg(all(feature = "mirrored", target_os = "linux"))]
+#[cfg_attr(miri, ignore)]
+#[test]
+fn mirrored_odd_size() {
+  use rbuf::MirroredRingBuf;
+
+  let mut buf = MirroredRingBuf::<[u8; 3]>::new(1).unwrap();
+  let len = buf.len();
+  assert_eq!(len % 4096, 0);
+  for i in 0..len + 1 {
+    let () = buf.push_back([i as u8; 3]);
+  }
+  assert_eq!(*buf.front(), [1; 3]);
+  assert_eq!(*buf.back(), [len as u8; 3]);
+}
+
+/// Check that an `MmapRingBuf` persists its contents and front index
+/// across reopening.
+#[cfg(feature = "mmap")]
+#[cfg_attr(miri, ignore)]
+#[test]
+fn mmap_reopen() {
+  use rbuf::MmapRingBuf;
+  use tempfile::tempdir;
+
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("ring");
+
+  let mut buf = MmapRingBuf::<u32>::open(&path, 4).unwrap();
+  assert_eq!(buf.len(), 4);
+  assert_eq!(buf.iter().copied().collect::<Vec<_>>(), vec![0, 0, 0, 0]);
+  for i in 1..=6 {
+    let () = buf.push_back(i);
+  }
+  buf[0] += 10;
+  let () = buf.flush().unwrap();
+  drop(buf);
+
+  let mut buf = MmapRingBuf::<u32>::open(&path, 4).unwrap();
+  assert_eq!(buf.as_slices(), (&[13, 4][..], &[5, 6][..]));
+  assert_eq!(*buf.front(), 13);
+  assert_eq!(*buf.back(), 6);
+  assert_eq!(buf[5], 4);
+
+  let () = buf.push_back(7);
+  let () = buf.flush_async().unwrap();
+  drop(buf);
+
+  let buf = MmapRingBuf::<u32>::open(&path, 4).unwrap();
+  assert_eq!(buf.iter().copied().collect::<Vec<_>>(), vec![4, 5, 6, 7]);
+}
+
+/// Make sure that `MmapRingBuf` refuses to open files that don't match
+/// the expected configuration.
+#[cfg(feature = "mmap")]
+#[cfg_attr(miri, ignore)]
+#[test]
+fn mmap_invalid() {
+  use std::fs::write;
+  use std::io::ErrorKind;
+
+  use rbuf::MmapRingBuf;
+  use tempfile::tempdir;
+
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("ring");
+  let buf = MmapRingBuf::<u32>::open(&path, 4).unwrap();
+  drop(buf);
+
+  let err = MmapRingBuf::<u32>::open(&path, 5).unwrap_err();
+  assert_eq!(err.kind(), ErrorKind::InvalidData);
+  let err = MmapRingBuf::<u16>::open(&path, 8).unwrap_err();
+  assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+  let () = write(&path, [0xff; 80]).unwrap();
+  let err = MmapRingBuf::<u32>::open(&path, 4).unwrap_err();
+  assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+/// Check that an `MmapRingBuf` file whose creation got interrupted
+/// before its header was persisted gets created anew.
+#[cfg(feature = "mmap")]
+#[cfg_attr(miri, ignore)]
+#[test]
+fn mmap_interrupted_create() {
+  use std::fs::write;
+
+  use rbuf::MmapRingBuf;
+  use tempfile::tempdir;
+
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("ring");
+  // A sized file with a zeroed header, as left behind by a crash.
+  let () = write(&path, [0; 80]).unwrap();
+
+  let mut buf = MmapRingBuf::<u32>::open(&path, 8).unwrap();
+  assert_eq!(buf.len(), 8);
+  let () = buf.push_back(1);
+  drop(buf);
+
+  let buf = MmapRingBuf::<u32>::open(&path, 8).unwrap();
+  assert_eq!(*buf.back(), 1);
+}
+
+/// Check that a `RecordLog` retains the most recent records across
+/// wrap arounds and reopening.
+#[cfg(feature = "record-log")]
+#[cfg_attr(miri, ignore)]
+#[test]
+fn record_log_wrap() {
+  use rbuf::RecordLog;
+  use tempfile::tempdir;
+
+  /// Check that `log` contains the most recent of the `pushed` records.
+  fn check(log: &RecordLog, pushed: &[Vec<u8>]) {
+    let records = log.iter().collect::<Vec<_>>();
+    assert_eq!(records.len(), log.len());
+    assert_eq!(log.next_seq(), pushed.len() as u64);
+
+    let first = pushed.len() - records.len();
+    for (i, record) in records.iter().enumerate() {
+      assert_eq!(record.seq, (first + i) as u64);
+      assert_eq!(record.data, pushed[first + i]);
+    }
+    assert_eq!(log.front(), records.first().copied());
+    assert_eq!(log.back(), records.last().copied());
+  }
+
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("log");
+  let mut log = RecordLog::open(&path, 200).unwrap();
+  assert!(log.is_empty());
+  assert_eq!(log.front(), None);
+  assert_eq!(log.back(), None);
+
+  let mut pushed = Vec::new();
+  for i in 0..200usize {
+    let record = vec![i as u8; (i * 7) % 41];
+    assert_eq!(log.push(&record), i as u64);
+    let () = pushed.push(record);
+    let () = check(&log, &pushed);
+    // Records only get evicted to make room, so the retained ones
+    // together with the most recently evicted one would not have fit
+    // into the data area. Up to one record header plus the largest
+    // payload's worth of bytes may be wasted at its end.
+    let first = pushed.len() - log.len();
+    if first > 0 {
+      let used = pushed[first - 1..]
+        .iter()
+        .map(|record| 16 + record.len())
+        .sum::<usize>();
+      assert!(used > 200 - (16 + 40), "{used}");
+    }
+
+    if i % 23 == 0 {
+      drop(log);
+      log = RecordLog::open(&path, 200).unwrap();
+      let () = check(&log, &pushed);
+    }
+  }
+
+  let record = vec![0xff; log.max_record_len()];
+  let _seq = log.push(&record);
+  assert_eq!(log.len(), 1);
+  assert_eq!(log.back().unwrap().data, record);
+}
+
+/// Make sure that a `RecordLog` recovers from a torn write, discarding
+/// only the damaged record.
+#[cfg(feature = "record-log")]
+#[cfg_attr(miri, ignore)]
+#[test]
+fn record_log_torn() {
+  use std::fs::read;
+  use std::fs::write;
+  use std::io::ErrorKind;
+
+  use rbuf::RecordLog;
+  use tempfile::tempdir;
+
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("log");
+  let mut log = RecordLog::open(&path, 128).unwrap();
+  for i in 0..10u8 {
+    let _seq = log.push(&[b'a' + i; 20]);
+  }
+  let expected = log
+    .iter()
+    .map(|record| record.data.to_vec())
+    .collect::<Vec<_>>();
+  let () = log.flush().unwrap();
+  drop(log);
+
+  // Damage the most recently written record.
+  let mut contents = read(&path).unwrap();
+  let idx = contents.windows(20).position(|w| w == [b'j'; 20]).unwrap();
+  contents[idx + 5] = 0;
+  let () = write(&path, &contents).unwrap();
+
+  let log = RecordLog::open(&path, 128).unwrap();
+  let records = log
+    .iter()
+    .map(|record| record.data.to_vec())
+    .collect::<Vec<_>>();
+  assert_eq!(records, expected[..expected.len() - 1]);
+  assert_eq!(log.next_seq(), 9);
+  drop(log);
+
+  let err = RecordLog::open(&path, 256).unwrap_err();
+  assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+/// Check that a `RecordLog` file for which the process crashed after
+/// sizing it but before its header was persisted gets created anew.
+#[cfg(feature = "record-log")]
+#[cfg_attr(miri, ignore)]
+#[test]
+fn record_log_interrupted_create() {
+  use std::fs::write;
+
+  use rbuf::RecordLog;
+  use tempfile::tempdir;
+
+  let dir = tempdir().unwrap();
+  let path = dir.path().join("log");
+  // A sized file with a zeroed header, as left behind by a crash.
+  let () = write(&path, [0; 64 + 128]).unwrap();
+
+  let mut log = RecordLog::open(&path, 128).unwrap();
+  assert!(log.is_empty());
+  assert_eq!(log.push(b"foobar"), 0);
+  drop(log);
+
+  let log = RecordLog::open(&path, 128).unwrap();
+  assert_eq!(log.len(), 1);
+  assert_eq!(log.back().unwrap().data, b"foobar");
+}
+
+/// The environment variable instructing a re-executed test binary to
+/// run the child part of a shared memory test. Its value is the name of
+/// the shared memory object to use.
+#[cfg(all(feature = "shm", target_os = "linux"))]
+const SHM_CHILD_VAR: &str = "RBUF_TEST_SHM_CHILD";
+
+/// Run the child part of the test `test` in a separate process, by
+/// re-executing the test binary.
+///
+/// Contrary to forking, doing so is safe in the presence of other
+/// threads, such as those of the test harness.
+#[cfg(all(feature = "shm", target_os = "linux"))]
+fn spawn_child(test: &str, name: &str) -> Child {
+  use std::env::current_exe;
+  use std::process::Command;
+  use std::process::Stdio;
+
+  Command::new(current_exe().unwrap())
+    .args(["--exact", test, "--test-threads=1"])
+    .env(SHM_CHILD_VAR, name)
+    .stdout(Stdio::null())
+    .spawn()
+    .unwrap()
+}
+
+/// Wait for the provided child process and check that it exited
+/// successfully.
+#[cfg(all(feature = "shm", target_os = "linux"))]
+fn wait(mut child: Child) {
+  let status = child.wait().unwrap();
+  assert!(status.success(), "child process failed: {status}");
+}
+
+/// Check that elements can be exchanged between processes by means of
+/// a shared memory ring buffer.
+#[cfg(all(feature = "shm", target_os = "linux"))]
+#[cfg_attr(miri, ignore)]
+#[test]
+fn shm_processes() {
+  use std::env;
+  use std::io::ErrorKind;
+  use std::process;
+  use std::thread;
+  use std::time::Duration;
+  use std::time::Instant;
+
+  use rbuf::shm::Consumer;
+  use rbuf::shm::Producer;
+
+  if let Ok(name) = env::var(SHM_CHILD_VAR) {
+    let mut producer = Producer::<u64>::create(&name, 16).unwrap();
+    for i in 0..1000 {
+      while producer.push(i).is_err() {
+        let () = thread::yield_now();
+      }
+    }
+    return
+  }
+
+  let name = format!("/rbuf-test-processes-{}", process::id());
+  let child = spawn_child("shm_processes", &name);
+
+  let deadline = Instant::now() + Duration::from_secs(30);
+  let mut consumer = loop {
+    match Consumer::<u64>::attach(&name) {
+      Ok(consumer) => break consumer,
+      Err(err) if matches!(err.kind(), ErrorKind::NotFound | ErrorKind::WouldBlock) => {
+        assert!(Instant::now() < deadline, "timed out waiting for producer");
+        let () = thread::yield_now();
+      },
+      Err(err) => panic!("failed to attach: {err}"),
+    }
+  };
+  assert_eq!(consumer.capacity(), 16);
+
+  let mut next = 0;
+  loop {
+    // Check liveness before draining, so that we don't miss any
+    // elements pushed just before the producer went away.
+    let alive = consumer.is_producer_alive();
+    while let Some(i) = consumer.pop() {
+      assert_eq!(i, next);
+      next += 1;
+    }
+
+    if !alive {
+      break
+    }
+    let () = thread::yield_now();
+  }
+  assert_eq!(next, 1000);
+  let () = wait(child);
+}
+
+/// Make sure that a consumer detects a producer that terminated without
+/// cleaning up and can still retrieve the elements it pushed.
+#[cfg(all(feature = "shm", target_os = "linux"))]
+#[cfg_attr(miri, ignore)]
+#[test]
+fn shm_producer_gone() {
+  use std::env;
+  use std::ffi::CString;
+  use std::fs::File;
+  use std::mem::forget;
+  use std::os::unix::io::AsRawFd as _;
+  use std::os::unix::io::FromRawFd as _;
+  use std::process;
+
+  use rbuf::shm::Consumer;
+  use rbuf::shm::Producer;
+
+  if let Ok(name) = env::var(SHM_CHILD_VAR) {
+    let mut producer = Producer::<u32>::create(&name, 4).unwrap();
+    let () = producer.push(1).unwrap();
+    let () = producer.push(2).unwrap();
+    // Leave behind the shared memory object, as a crashing process
+    // would.
+    forget(producer);
+    return
+  }
+
+  let name = format!("/rbuf-test-gone-{}", process::id());
+  let () = wait(spawn_child("shm_producer_gone", &name));
+
+  let mut consumer = Consumer::<u32>::attach(&name).unwrap();
+  assert!(!consumer.is_producer_alive());
+  assert_eq!(consumer.len(), 2);
+  let mut elems = [0; 4];
+  assert_eq!(consumer.pop_slice(&mut elems), 2);
+  assert_eq!(elems[..2], [1, 2]);
+  assert_eq!(consumer.pop(), None);
+
+  let c_name = CString::new(name.clone()).unwrap();
+  // SAFETY: The name is a valid NUL terminated string.
+  let fd = unsafe { libc::shm_open(c_name.as_ptr(), libc::O_RDONLY, 0) };
+  assert!(fd >= 0);
+  // SAFETY: We just opened the file descriptor and exclusively own it.
+  let file = unsafe { File::from_raw_fd(fd) };
+  // Emulate a consumer concurrently checking for the producer.
+  // SAFETY: The file descriptor is valid.
+  let rc = unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_SH) };
+  assert_eq!(rc, 0);
+
+  // A new producer replaces the stale object nevertheless.
+  let mut producer = Producer::<u32>::create(&name, 4).unwrap();
+  drop(file);
+  let mut consumer = Consumer::<u32>::attach(&name).unwrap();
+  assert!(consumer.is_producer_alive());
+  let () = producer.push(3).unwrap();
+  assert_eq!(consumer.pop(), Some(3));
+  drop(producer);
+  assert!(!consumer.is_producer_alive());
+
+  // SAFETY: The name is a valid NUL terminated string.
+  let rc = unsafe { libc::shm_unlink(c_name.as_ptr()) };
+  assert_eq!(rc, -1);
+}
+
+/// Check that attaching to a shared memory ring buffer validates its
+/// existence and element size, that pushing to a full one fails, and
+/// that a live producer's ring buffer cannot be replaced.
+#[cfg(all(feature = "shm", target_os = "linux"))]
+#[cfg_attr(miri, ignore)]
+#[test]
+fn shm_attach() {
+  use std::io::ErrorKind;
+  use std::process;
+
+  use rbuf::shm::Consumer;
+  use rbuf::shm::Producer;
+
+  let name = format!("/rbuf-test-attach-{}", process::id());
+  let err = Consumer::<u16>::attach(&name).unwrap_err();
+  assert_eq!(err.kind(), ErrorKind::NotFound);
+
+  let mut producer = Producer::<u16>::create(&name, 3).unwrap();
+  assert_eq!(producer.push_slice(&[1, 2, 3, 4]), 3);
+  assert_eq!(producer.push(5), Err(5));
+  assert_eq!(producer.len(), 3);
+
+  let err = Consumer::<u32>::attach(&name).unwrap_err();
+  assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+  // The ring buffer is still in use by a live producer.
+  let err = Producer::<u16>::create(&name, 3).unwrap_err();
+  assert_eq!(err.kind(), ErrorKind::AddrInUse);
+
+  let mut consumer = Consumer::<u16>::attach(&name).unwrap();
+  assert_eq!(consumer.pop(), Some(1));
+  assert_eq!(producer.push(5), Ok(()));
+  let mut elems = [0; 8];
+  assert_eq!(consumer.pop_slice(&mut elems), 3);
+  assert_eq!(elems[..3], [2, 3, 5]);
+  assert!(consumer.is_empty());
+  assert!(producer.is_empty());
+
+  drop(producer);
+  let err = Consumer::<u16>::attach(&name).unwrap_err();
+  assert_eq!(err.kind(), ErrorKind::NotFound);
+}
+
+/// Check that a `RecordRing` places records contiguously, wrapping
+/// around and evicting the oldest ones as necessary.
+#[test]
+fn record_ring_wrap() {
+  let mut ring = RecordRing::new(20);
+  assert!(ring.is_empty());
+  assert_eq!(ring.front(), None);
+  assert_eq!(ring.back(), None);
+  assert_eq!(ring.max_record_len(), 16);
+
+  assert_eq!(ring.push(b"aaaa"), 0);
+  assert_eq!(ring.push(b"bb"), 0);
+  assert_eq!(ring.push(b""), 0);
+  assert_eq!(ring.len(), 3);
+  assert_eq!(ring.front(), Some(&b"aaaa"[..]));
+  assert_eq!(ring.back(), Some(&b""[..]));
+
+  // Only two bytes are left at the end, so the record is placed at the
+  // start, evicting the first one.
+  assert_eq!(ring.push(b"cc"), 1);
+  assert_eq!(
+    ring.iter().collect::<Vec<_>>(),
+    vec![&b"bb"[..], &b""[..], &b"cc"[..]]
+  );
+
+  // Evicting the remainder of the older records hands all space at the
+  // end of the buffer back.
+  assert_eq!(ring.push(b"dddddd"), 2);
+  assert_eq!(ring.push(b""), 0);
+  assert_eq!(
+    (&ring).into_iter().collect::<Vec<_>>(),
+    vec![&b"cc"[..], &b"dddddd"[..], &b""[..]]
+  );
+
+  assert_eq!(ring.push(&[b'f'; 16]), 3);
+  assert_eq!(ring.len(), 1);
+  assert_eq!(ring.front(), ring.back());
+
+  let () = ring.clear();
+  assert!(ring.is_empty());
+  assert_eq!(ring.iter().len(), 0);
+}
+
+/// Check that a `RecordRing` always retains the most recently pushed
+/// records.
+#[test]
+fn record_ring_retained() {
+  let mut ring = RecordRing::new(64);
+  let mut pushed = Vec::new();
+
+  for i in 0..500usize {
+    let record = vec![i as u8; (i * 13) % 29];
+    let len = ring.len();
+    let evicted = ring.push(&record);
+    assert_eq!(ring.len(), len + 1 - evicted);
+    let () = pushed.push(record);
+
+    let records = ring.iter().collect::<Vec<_>>();
+    assert_eq!(records.len(), ring.len());
+    let first = pushed.len() - records.len();
+    for (record, expected) in records.iter().zip(&pushed[first..]) {
+      assert_eq!(record, expected);
+    }
+    let size = records.iter().map(|record| 4 + record.len()).sum::<usize>();
+    assert!(size <= ring.capacity());
+    assert_eq!(ring.back(), Some(&pushed[pushed.len() - 1][..]));
+  }
+}
+
+/// Make sure that pushing a record larger than a `RecordRing` can hold
+/// panics.
+#[test]
+#[should_panic(expected = "record of 13 bytes exceeds maximum record length 12")]
+fn record_ring_too_large() {
+  let mut ring = RecordRing::new(16);
+  let _evicted = ring.push(&[0; 13]);
+}
+
+/// Check that we can allocate a `RingBuf`'s storage through a custom
+/// allocator.
+#[cfg(feature = "allocator-api2")]
+#[test]
+fn custom_allocator() {
+  use std::alloc::Layout;
+  use std::cell::Cell;
+  use std::ptr::NonNull;
+
+  use allocator_api2::alloc::AllocError;
+  use allocator_api2::alloc::Allocator;
+  use allocator_api2::alloc::Global;
+  use allocator_api2::vec::Vec;
+
+  /// An allocator keeping track of the number of live allocations.
+  #[derive(Debug)]
+  struct Counting<'c>(&'c Cell<usize>);
+
+  unsafe impl Allocator for Counting<'_> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+      let () = self.0.set(self.0.get() + 1);
+      Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+      let () = self.0.set(self.0.get() - 1);
+      unsafe { Global.deallocate(ptr, layout) }
+    }
+  }
+
+  let count = Cell::new(0);
+  let mut buf = RingBuf::<usize, _>::new_in(4, Counting(&count));
+  assert_eq!(count.get(), 1);
+  let () = buf.push_back(1);
+  assert_eq!(*buf.back(), 1);
+  drop(buf);
+  assert_eq!(count.get(), 0);
+
+  let mut vec = Vec::new_in(Counting(&count));
+  let () = vec.extend([1, 2, 3]);
+  let buf = RingBuf::from_vec_in(vec);
+  assert_eq!(count.get(), 1);
+  assert!(buf.iter().eq([1, 2, 3].iter()));
+  drop(buf);
+  assert_eq!(count.get(), 0);
+}